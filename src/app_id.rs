@@ -0,0 +1,149 @@
+use super::prelude::*;
+use super::WolframalphaError;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The AppID of a wolframalpha application. It is a secret, so `Debug` and
+/// `Display` print `REDACTED` instead of it.
+///
+/// AppIDs created with `From` are not checked, but a client refuses to send
+/// requests with an invalid one, failing with
+/// [`WolframalphaError::InvalidAppId`]. Use [`AppId::parse`] to check it
+/// up front.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct AppId(String);
+
+impl AppId {
+    /// The environment variable [`AppId::from_env`] reads
+    pub const ENV_VAR: &'static str = "WOLFRAM_APP_ID";
+
+    /// Checks that `app_id` looks like an AppID: between 1 and 64 ascii
+    /// letters, digits and dashes
+    pub fn parse(app_id: &str) -> Result<Self, WolframalphaError> {
+        let app_id = AppId(app_id.trim().to_string());
+        if app_id.is_valid() {
+            Ok(app_id)
+        } else {
+            Err(WolframalphaError::InvalidAppId)
+        }
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        (1..=64).contains(&self.0.len())
+            && self
+                .0
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    }
+
+    /// Reads the AppID from the `WOLFRAM_APP_ID` environment variable
+    pub fn from_env() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        match std::env::var(Self::ENV_VAR) {
+            Ok(v) => Ok(Self::parse(&v)?),
+            Err(e) => Err(format!("{}: {}", Self::ENV_VAR, e).into()),
+        }
+    }
+
+    /// Reads the AppID from a file containing just the AppID. Empty lines
+    /// and lines starting with `#` are ignored.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let contents = std::fs::read_to_string(path.as_ref())?;
+
+        let line = contents
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with('#'))
+            .unwrap_or("");
+
+        Ok(Self::parse(line)?)
+    }
+
+    /// Where [`AppId::load`] looks for the AppID file:
+    /// `$XDG_CONFIG_HOME/wolframalpha/app_id`, or
+    /// `~/.config/wolframalpha/app_id` if `XDG_CONFIG_HOME` is not set
+    pub fn default_file() -> Option<PathBuf> {
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+
+        Some(config.join("wolframalpha").join("app_id"))
+    }
+
+    /// Reads the AppID from the environment if `WOLFRAM_APP_ID` is set, and
+    /// from [`AppId::default_file`] otherwise
+    pub fn load() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        if std::env::var_os(Self::ENV_VAR).is_some() {
+            return Self::from_env();
+        }
+
+        match Self::default_file() {
+            Some(path) if path.exists() => Self::from_file(path),
+            Some(path) => Err(format!(
+                "{} is not set and {} does not exist",
+                Self::ENV_VAR,
+                path.display()
+            )
+            .into()),
+            None => Err(format!("{} is not set", Self::ENV_VAR).into()),
+        }
+    }
+
+    /// The AppID itself, for sending it to the api
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for AppId {
+    fn from(app_id: String) -> Self {
+        AppId(app_id)
+    }
+}
+
+impl From<&str> for AppId {
+    fn from(app_id: &str) -> Self {
+        AppId(app_id.to_string())
+    }
+}
+
+impl FromStr for AppId {
+    type Err = WolframalphaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Debug for AppId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AppId(REDACTED)")
+    }
+}
+
+impl fmt::Display for AppId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("REDACTED")
+    }
+}
+
+#[test]
+fn app_id_parse_and_redact() {
+    let app_id = AppId::parse(" ABC123-DEF4567890\n").unwrap();
+    assert_eq!(app_id.expose(), "ABC123-DEF4567890");
+    assert_eq!(
+        format!("{:?} {}", app_id, app_id),
+        "AppId(REDACTED) REDACTED"
+    );
+
+    assert!(AppId::parse("DEMO").is_ok());
+    assert!(AppId::parse("").is_err());
+    assert!(AppId::parse("ABC 123").is_err());
+    assert!(AppId::parse("ABC&i=x").is_err());
+
+    let path = std::env::temp_dir().join(format!("wolframalpha_api_appid_{}", std::process::id()));
+    std::fs::write(&path, "# wolframalpha\n\nABC123-DEF4567890\n").unwrap();
+    assert_eq!(AppId::from_file(&path).unwrap(), app_id);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,288 @@
+//! Command line interface to the wolframalpha apis, for trying out questions
+//! without writing code. Requires the `cli` feature.
+//!
+//! The AppID is read from the `WOLFRAM_APP_ID` environment variable, or
+//! from the file described in [`AppId::default_file`].
+use std::error::Error;
+use std::process::exit;
+use wolframalpha_api::render::{to_terminal, TerminalOptions};
+use wolframalpha_api::{
+    AppId, Client, LatLong, Location, RequestOptions, UnitSystem, WolframalphaError,
+};
+
+const USAGE: &str = "\
+usage: wolfram <command> [options] <question>...
+
+commands:
+    simple -o <file>   save the image answer of the simple api to <file>
+    short              print the short answer
+    spoken             print the spoken answer
+    full               print the pods of the full results
+    raw                print the json response of the full results api
+
+options:
+    -o, --output <file>        where `simple` saves the image
+    --units <metric|imperial>  measurement system of the answer
+    --location <name>          ask from a named place, e.g. \"Boston, MA\"
+    --latlong <lat,long>       ask from a position
+    --ip <address>             ask from the location of an ip address
+    --color                    highlight the output of `full`
+    --width <columns>          wrap the output of `full`
+    -h, --help                 print this message
+    --                         end of the options, the rest is the question
+
+The options go before the question. Put the question after `--` if it
+starts with a dash followed by a letter.
+
+The AppID is read from the WOLFRAM_APP_ID environment variable, or from
+~/.config/wolframalpha/app_id.";
+
+#[derive(Debug)]
+enum Command {
+    Simple,
+    Short,
+    Spoken,
+    Full,
+    Raw,
+}
+
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    question: String,
+    output: Option<String>,
+    units: Option<UnitSystem>,
+    location: Option<Location>,
+    render: TerminalOptions,
+}
+
+/// Words starting with a dash and a letter, so questions like `-2^2 + 3`
+/// don't need `--`
+fn is_option(arg: &str) -> bool {
+    arg.strip_prefix('-').map_or(false, |a| {
+        a.starts_with(|c: char| c == '-' || c.is_ascii_alphabetic())
+    })
+}
+
+/// Parses the arguments after the program name, returning `None` if help
+/// was asked for
+fn parse_args(args: &[String]) -> Result<Option<Args>, String> {
+    let mut args = args.iter();
+
+    let command = match args.next().map(String::as_str) {
+        Some("-h") | Some("--help") => return Ok(None),
+        Some("simple") => Command::Simple,
+        Some("short") => Command::Short,
+        Some("spoken") => Command::Spoken,
+        Some("full") => Command::Full,
+        Some("raw") => Command::Raw,
+        Some(c) => return Err(format!("unknown command `{}`", c)),
+        None => return Err("missing command".to_string()),
+    };
+
+    let mut question = vec![];
+    let mut output = None;
+    let mut units = None;
+    let mut location = None;
+    let mut render = TerminalOptions::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", name))
+        };
+
+        match arg.as_str() {
+            "--" => question.extend(args.by_ref().cloned()),
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--output" => output = Some(value(arg)?),
+            "--units" => {
+                units = Some(match value(arg)?.as_str() {
+                    "metric" => UnitSystem::Metric,
+                    "imperial" => UnitSystem::Imperial,
+                    u => return Err(format!("unknown unit system `{}`", u)),
+                })
+            }
+            "--location" => location = Some(Location::Named(value(arg)?)),
+            "--latlong" => {
+                let v = value(arg)?;
+                let parsed = v.split_once(',').and_then(|(lat, long)| {
+                    Some((lat.trim().parse().ok()?, long.trim().parse().ok()?))
+                });
+                match parsed {
+                    Some((lat, long)) => {
+                        location = Some(Location::LatLong(LatLong::new(lat, long)))
+                    }
+                    None => return Err(format!("invalid latlong `{}`", v)),
+                }
+            }
+            "--ip" => {
+                let v = value(arg)?;
+                match v.parse() {
+                    Ok(ip) => location = Some(Location::Ip(ip)),
+                    Err(_) => return Err(format!("invalid ip address `{}`", v)),
+                }
+            }
+            "--color" => render.color = true,
+            "--width" => {
+                let v = value(arg)?;
+                match v.parse() {
+                    Ok(w) => render.width = Some(w),
+                    Err(_) => return Err(format!("invalid width `{}`", v)),
+                }
+            }
+            a if is_option(a) => return Err(format!("unknown option `{}`", a)),
+            // the question starts at the first word that isn't an option,
+            // the rest is part of it even if it looks like an option
+            word => {
+                question.push(word.to_string());
+                question.extend(args.by_ref().cloned());
+            }
+        }
+    }
+
+    if question.is_empty() {
+        return Err("missing question".to_string());
+    }
+    if matches!(command, Command::Simple) && output.is_none() {
+        return Err("simple needs --output".to_string());
+    }
+
+    Ok(Some(Args {
+        command,
+        question: question.join(" "),
+        output,
+        units,
+        location,
+        render,
+    }))
+}
+
+async fn run(args: Args) -> Result<Result<(), WolframalphaError>, Box<dyn Error + Send + Sync>> {
+    let mut client = Client::new(AppId::load()?);
+    if let Some(units) = args.units {
+        client = client.with_units(units);
+    }
+    let options = RequestOptions {
+        location: args.location,
+        ..Default::default()
+    };
+    let question = args.question.as_str();
+
+    match args.command {
+        Command::Simple => {
+            let image = match client.retrieve_bytes_with(question, &options).await? {
+                Ok(i) => i,
+                Err(e) => return Ok(Err(e)),
+            };
+            // checked by parse_args
+            let output = args.output.unwrap();
+            std::fs::write(&output, &image)?;
+            eprintln!("saved {} bytes to {}", image.len(), output);
+        }
+        Command::Short => match client.short_answer_with(question, &options).await? {
+            Ok(answer) => println!("{}", answer),
+            Err(e) => return Ok(Err(e)),
+        },
+        Command::Spoken => match client.spoken_answer_with(question, &options).await? {
+            Ok(answer) => println!("{}", answer),
+            Err(e) => return Ok(Err(e)),
+        },
+        Command::Full => match client.query_with(question, &options).await? {
+            Ok(result) => {
+                if !result.success {
+                    eprintln!("wolframalpha did not understand the question");
+                }
+                print!("{}", to_terminal(&result, &args.render));
+            }
+            Err(e) => return Ok(Err(e)),
+        },
+        Command::Raw => match client.query_raw(question, &options).await? {
+            Ok(body) => {
+                let json: serde_json::Value = serde_json::from_slice(&body)?;
+                println!("{}", serde_json::to_string_pretty(&json)?);
+            }
+            Err(e) => return Ok(Err(e)),
+        },
+    }
+
+    Ok(Ok(()))
+}
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let args = match parse_args(&args) {
+        Ok(Some(a)) => a,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("wolfram: {}\n\n{}", e, USAGE);
+            exit(2);
+        }
+    };
+
+    match run(args).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            eprintln!("wolfram: {}", e);
+            exit(1);
+        }
+        Err(e) => {
+            eprintln!("wolfram: {}", e);
+            exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+fn split(args: &str) -> Vec<String> {
+    args.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn parse_args_questions() {
+    let args = parse_args(&split("short --units metric -2^2 + 3"))
+        .unwrap()
+        .unwrap();
+    assert!(matches!(args.command, Command::Short));
+    assert!(matches!(args.units, Some(UnitSystem::Metric)));
+    assert_eq!(args.question, "-2^2 + 3");
+
+    let args = parse_args(&split("full --width 60 -- -x -h --units"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(args.render.width, Some(60));
+    assert_eq!(args.question, "-x -h --units");
+
+    let args = parse_args(&split("spoken what does -h mean"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(args.question, "what does -h mean");
+
+    assert_eq!(
+        parse_args(&split("short -x pi")).unwrap_err(),
+        "unknown option `-x`"
+    );
+    assert_eq!(
+        parse_args(&split("simple pi")).unwrap_err(),
+        "simple needs --output"
+    );
+    assert_eq!(
+        parse_args(&split("short --")).unwrap_err(),
+        "missing question"
+    );
+}
+
+#[test]
+fn parse_args_help() {
+    assert!(parse_args(&split("--help")).unwrap().is_none());
+    assert!(parse_args(&split("short -h")).unwrap().is_none());
+    assert!(parse_args(&split("short --units metric --help pi"))
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,402 @@
+//! A synchronous version of [`Client`](crate::Client), for programs that
+//! don't use an async runtime. Like `reqwest::blocking` it must not be used
+//! from within one. Requires the `blocking` feature.
+use super::prelude::*;
+use super::*;
+use crate::app_id::AppId;
+use crate::client::{Api, Config, RequestIo};
+use crate::limit::{Quota, RateLimiter};
+use crate::response::{raw_response::RawApiResponse, Image, QueryResult, Sound};
+use crate::retry::RetryPolicy;
+use futures::future::{self, BoxFuture};
+use std::time::Duration;
+
+/// Performs the http requests of a blocking [`Client`], like
+/// [`HttpTransport`] does for the async one
+pub trait BlockingTransport: Send + Sync {
+    /// Performs a GET request to `url`. Responses with error statuses should
+    /// be returned as responses, not as errors.
+    fn get(&self, url: &str) -> Result<HttpResponse, Box<dyn Error + Send + Sync>>;
+}
+
+/// The default blocking transport, built on `reqwest::blocking`
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestBlockingTransport {
+    client: reqwest::blocking::Client,
+}
+
+impl ReqwestBlockingTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses an existing `reqwest` client, for example one configured with a
+    /// proxy or timeouts
+    pub fn from_client(client: reqwest::blocking::Client) -> Self {
+        ReqwestBlockingTransport { client }
+    }
+}
+
+impl BlockingTransport for ReqwestBlockingTransport {
+    fn get(&self, url: &str) -> Result<HttpResponse, Box<dyn Error + Send + Sync>> {
+        // the url contains the AppID, keep it out of error messages
+        let response = self.client.get(url).send().map_err(|e| e.without_url())?;
+
+        Ok(HttpResponse {
+            status: response.status().as_u16(),
+            body: response.bytes().map_err(|e| e.without_url())?,
+        })
+    }
+}
+
+/// Performs the steps of [`Config::get`] through a [`BlockingTransport`].
+/// Each step is done before its future is returned, so driving the request
+/// with `block_on` never waits on anything else.
+struct BlockingIo<'a, T>(&'a T);
+
+impl<T: BlockingTransport> RequestIo for BlockingIo<'_, T> {
+    fn get(&self, url: &str) -> BoxFuture<'_, Result<HttpResponse, Box<dyn Error + Send + Sync>>> {
+        Box::pin(future::ready(self.0.get(url)))
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        std::thread::sleep(duration);
+        Box::pin(future::ready(()))
+    }
+
+    fn count<'a>(
+        &'a self,
+        quota: &'a Quota,
+    ) -> BoxFuture<'a, Result<Result<(), WolframalphaError>, Box<dyn Error + Send + Sync>>> {
+        Box::pin(future::ready(quota.acquire()))
+    }
+}
+
+/// A blocking handle to the wolframalpha api, see [`crate::Client`] for
+/// details on the methods. It has no cache, so
+/// [`RequestOptions::bypass_cache`] has no effect.
+#[derive(Debug, Clone)]
+pub struct Client<T = ReqwestBlockingTransport> {
+    config: Config,
+    transport: T,
+}
+
+impl Client {
+    /// Creates a new client
+    ///
+    /// # Arguments
+    ///
+    /// * `app_id` - The AppID of your wolframalpha application
+    pub fn new(app_id: impl Into<AppId>) -> Self {
+        Self::with_transport(app_id, ReqwestBlockingTransport::new())
+    }
+}
+
+impl<T: BlockingTransport> Client<T> {
+    /// Creates a new client that performs its requests through `transport`
+    ///
+    /// # Arguments
+    ///
+    /// * `app_id` - The AppID of your wolframalpha application
+    /// * `transport` - The http implementation to use
+    pub fn with_transport(app_id: impl Into<AppId>, transport: T) -> Self {
+        Client {
+            config: Config::new(app_id.into()),
+            transport,
+        }
+    }
+
+    /// See [`crate::Client::with_did_you_mean_retry`]
+    pub fn with_did_you_mean_retry(mut self, min_score: f32) -> Self {
+        self.config.did_you_mean_threshold = Some(min_score);
+        self
+    }
+
+    /// See [`crate::Client::with_reinterpret`]
+    pub fn with_reinterpret(mut self, reinterpret: bool) -> Self {
+        self.config.reinterpret = reinterpret;
+        self
+    }
+
+    /// See [`crate::Client::with_signing_secret`]
+    pub fn with_signing_secret(mut self, secret: impl Into<String>) -> Self {
+        self.config.signing_secret = Some(crate::sign::SigningSecret::new(secret.into()));
+        self
+    }
+
+    /// See [`crate::Client::with_formats`]
+    pub fn with_formats(mut self, formats: &[Format]) -> Self {
+        self.config.formats = formats.to_vec();
+        self
+    }
+
+    /// See [`crate::Client::with_location`]
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.config.location = Some(location);
+        self
+    }
+
+    /// See [`crate::Client::with_units`]
+    pub fn with_units(mut self, units: UnitSystem) -> Self {
+        self.config.units = Some(units);
+        self
+    }
+
+    /// See [`crate::Client::with_rate_limit`]
+    pub fn with_rate_limit(mut self, limiter: RateLimiter) -> Self {
+        self.config.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// See [`crate::Client::with_quota`]
+    pub fn with_quota(mut self, quota: Quota) -> Self {
+        self.config.quota = Some(quota);
+        self
+    }
+
+    /// See [`crate::Client::with_retry`]
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.config.retry = Some(policy);
+        self
+    }
+
+    /// Performs a GET request through the transport, see [`Config::get`]
+    fn get(
+        &self,
+        url: &str,
+        metered: bool,
+    ) -> Result<Result<HttpResponse, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        futures::executor::block_on(self.config.get(&BlockingIo(&self.transport), url, metered))
+    }
+
+    /// Makes an api call, subject to the quota, rate limit and retry policy
+    fn send(
+        &self,
+        url: String,
+    ) -> Result<Result<HttpResponse, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.get(&url, true)
+    }
+
+    fn retrieve_v1(
+        &self,
+        api: Api,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<HttpResponse, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        if question.trim() == "" {
+            return Ok(Err(WolframalphaError::InvalidQuestion));
+        }
+
+        let url = match self.config.url(api, question, options) {
+            Ok(u) => u,
+            Err(e) => return Ok(Err(e)),
+        };
+        let response = match self.send(url)? {
+            Ok(r) => r,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        if response.status == 501 {
+            return Ok(Err(WolframalphaError::InvalidQuestion));
+        }
+
+        Ok(Ok(response.error_for_status()?))
+    }
+
+    /// Performs a simple api request, returning the raw bytes of the image
+    pub fn retrieve_bytes(
+        &self,
+        question: &str,
+    ) -> Result<Result<Bytes, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.retrieve_bytes_with(question, &RequestOptions::default())
+    }
+
+    /// [`Client::retrieve_bytes`] with options for this request only
+    pub fn retrieve_bytes_with(
+        &self,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<Bytes, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        Ok(match self.retrieve_v1(Api::Simple, question, options)? {
+            Ok(response) => Ok(response.body),
+            Err(e) => Err(e),
+        })
+    }
+
+    /// Performs a simple api request, returning you the image
+    #[cfg(feature = "image")]
+    pub fn retrieve_image(
+        &self,
+        question: &str,
+    ) -> Result<Result<image::DynamicImage, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        Ok(match self.retrieve_bytes(question)? {
+            Ok(bytes) => Ok(get_image::decode(&bytes)?),
+            Err(e) => Err(e),
+        })
+    }
+
+    /// Performs a short answers api request
+    pub fn short_answer(
+        &self,
+        question: &str,
+    ) -> Result<Result<String, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.short_answer_with(question, &RequestOptions::default())
+    }
+
+    /// [`Client::short_answer`] with options for this request only
+    pub fn short_answer_with(
+        &self,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<String, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        Ok(match self.retrieve_v1(Api::Short, question, options)? {
+            Ok(response) => Ok(String::from_utf8(response.body.to_vec())?),
+            Err(e) => Err(e),
+        })
+    }
+
+    /// Performs a spoken results api request
+    pub fn spoken_answer(
+        &self,
+        question: &str,
+    ) -> Result<Result<String, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.spoken_answer_with(question, &RequestOptions::default())
+    }
+
+    /// [`Client::spoken_answer`] with options for this request only
+    pub fn spoken_answer_with(
+        &self,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<String, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        Ok(match self.retrieve_v1(Api::Spoken, question, options)? {
+            Ok(response) => Ok(String::from_utf8(response.body.to_vec())?),
+            Err(e) => Err(e),
+        })
+    }
+
+    fn query_once(
+        &self,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<QueryResult, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        let url = match self.config.url(Api::FullResults, question, options) {
+            Ok(u) => u,
+            Err(e) => return Ok(Err(e)),
+        };
+        let response = match self.send(url)? {
+            Ok(r) => r.error_for_status()?,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        let raw: RawApiResponse = serde_json::from_slice(&response.body)?;
+
+        Ok(Ok(raw.into()))
+    }
+
+    /// Performs a full results api request
+    pub fn query(
+        &self,
+        question: &str,
+    ) -> Result<Result<QueryResult, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.query_with(question, &RequestOptions::default())
+    }
+
+    /// [`Client::query`] with options for this request only
+    pub fn query_with(
+        &self,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<QueryResult, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        if question.trim() == "" {
+            return Ok(Err(WolframalphaError::InvalidQuestion));
+        }
+
+        let result = match self.query_once(question, options)? {
+            Ok(r) => r,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        if let Some(suggestion) = self.config.did_you_mean(&result) {
+            let retried = match self.query_once(&suggestion.val, options)? {
+                Ok(r) => r,
+                Err(e) => return Ok(Err(e)),
+            };
+            return Ok(Ok(Config::substitute(question, suggestion, retried)));
+        }
+
+        Ok(Ok(result))
+    }
+
+    fn fetch(&self, url: &str) -> Result<Bytes, Box<dyn Error + Send + Sync>> {
+        match self.get(url, false)? {
+            Ok(response) => Ok(response.error_for_status()?.body),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Downloads the image a response points to, such as a subpod image
+    pub fn fetch_image_bytes(&self, image: &Image) -> Result<Bytes, Box<dyn Error + Send + Sync>> {
+        self.fetch(&image.source)
+    }
+
+    /// Downloads and decodes the image a response points to
+    #[cfg(feature = "image")]
+    pub fn fetch_image(
+        &self,
+        image: &Image,
+    ) -> Result<image::DynamicImage, Box<dyn Error + Send + Sync>> {
+        get_image::decode(&self.fetch_image_bytes(image)?)
+    }
+
+    /// Downloads the audio of a pod sound
+    pub fn fetch_sound(&self, sound: &Sound) -> Result<Bytes, Box<dyn Error + Send + Sync>> {
+        self.fetch(&sound.url)
+    }
+}
+
+#[test]
+fn blocking_client_with_transport() {
+    use crate::testing::{fixtures, json, status, Matcher, MockTransport};
+
+    let transport = MockTransport::new()
+        .on(Matcher::api("v1/result"), status(501))
+        .on(Matcher::api("v2/query"), json(fixtures::POPULATION_FRANCE));
+    let client =
+        Client::with_transport("APPID", transport.clone()).with_units(UnitSystem::Imperial);
+
+    assert!(matches!(
+        client.short_answer("asdfgh").unwrap(),
+        Err(WolframalphaError::InvalidQuestion)
+    ));
+    assert!(client.query("population france").unwrap().unwrap().success);
+    let options = RequestOptions {
+        location: Some(Location::Named("Boston, MA".to_string())),
+        ..Default::default()
+    };
+    let _ = client.spoken_answer_with("weather", &options);
+
+    assert_eq!(
+        transport.requests(),
+        vec![
+            "http://api.wolframalpha.com/v1/result?appid=APPID&i=asdfgh&units=nonmetric",
+            "http://api.wolframalpha.com/v2/query?appid=APPID&input=population%20france\
+             &output=json&units=nonmetric",
+            "http://api.wolframalpha.com/v1/spoken?appid=APPID&i=weather&units=nonmetric\
+             &location=Boston%2C%20MA",
+        ]
+    );
+}
+
+#[test]
+fn blocking_errors_hide_app_id() {
+    use crate::testing::{status, Matcher, MockTransport};
+
+    let transport = MockTransport::new().on(Matcher::any(), status(500));
+    let client = Client::with_transport("SECRET-APPID", transport);
+
+    let e = client.spoken_answer("weather").unwrap_err();
+    assert_eq!(e.to_string(), "http status 500");
+    let e = client.query("weather").unwrap_err();
+    assert!(!e.to_string().contains("SECRET-APPID"));
+}
@@ -0,0 +1,192 @@
+use crate::client::Api;
+use crate::transport::HttpResponse;
+use futures::future::BoxFuture;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// A response stored in a cache, together with when it stops being valid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResponse {
+    pub response: HttpResponse,
+    pub expires: SystemTime,
+}
+
+/// Storage for cached responses. Caching is best effort, so backends should
+/// swallow their own errors and act as if the entry was missing.
+pub trait CacheBackend: Send + Sync {
+    /// Looks up `key`, returning `None` if it is missing or has expired
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<HttpResponse>>;
+
+    fn put(&self, key: String, value: CachedResponse) -> BoxFuture<'_, ()>;
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    /// Entries with the generation of their last use
+    entries: HashMap<String, (u64, CachedResponse)>,
+    /// Keys by the generation of their last use, from least to most
+    /// recently used
+    order: BTreeMap<u64, String>,
+    generation: u64,
+}
+
+impl Lru {
+    /// Marks `key` as the most recently used entry
+    fn touch(&mut self, key: &str) {
+        if let Some((generation, _)) = self.entries.get_mut(key) {
+            if let Some(k) = self.order.remove(generation) {
+                self.generation += 1;
+                *generation = self.generation;
+                self.order.insert(self.generation, k);
+            }
+        }
+    }
+
+    fn insert(&mut self, key: String, value: CachedResponse) {
+        self.remove(&key);
+        self.generation += 1;
+        self.order.insert(self.generation, key.clone());
+        self.entries.insert(key, (self.generation, value));
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some((generation, _)) = self.entries.remove(key) {
+            self.order.remove(&generation);
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        if let Some(&oldest) = self.order.keys().next() {
+            if let Some(k) = self.order.remove(&oldest) {
+                self.entries.remove(&k);
+            }
+        }
+    }
+}
+
+/// An in-memory cache that evicts the least recently used entry once it
+/// holds `capacity` entries
+#[derive(Debug, Clone)]
+pub struct MemoryCache {
+    capacity: usize,
+    state: Arc<Mutex<Lru>>,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize) -> Self {
+        MemoryCache {
+            capacity: capacity.max(1),
+            state: Arc::default(),
+        }
+    }
+}
+
+impl CacheBackend for MemoryCache {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<HttpResponse>> {
+        let mut state = self.state.lock().unwrap();
+
+        let res = match state.entries.get(key) {
+            Some((_, e)) if e.expires > SystemTime::now() => Some(e.response.clone()),
+            Some(_) => {
+                state.remove(key);
+                None
+            }
+            None => None,
+        };
+        if res.is_some() {
+            state.touch(key);
+        }
+
+        Box::pin(async move { res })
+    }
+
+    fn put(&self, key: String, value: CachedResponse) -> BoxFuture<'_, ()> {
+        let mut state = self.state.lock().unwrap();
+
+        state.insert(key, value);
+        while state.entries.len() > self.capacity {
+            state.evict_oldest();
+        }
+
+        Box::pin(async {})
+    }
+}
+
+/// Response caching for a [`Client`](crate::Client). Responses are cached
+/// per api, question and client options, and only if the request succeeded.
+#[derive(Clone)]
+pub struct Cache {
+    backend: Arc<dyn CacheBackend>,
+    ttls: HashMap<Api, Duration>,
+}
+
+impl fmt::Debug for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cache").field("ttls", &self.ttls).finish()
+    }
+}
+
+impl Cache {
+    /// Caches the responses of every api for one hour
+    pub fn new(backend: impl CacheBackend + 'static) -> Self {
+        let hour = Duration::from_secs(60 * 60);
+        Cache {
+            backend: Arc::new(backend),
+            ttls: Api::ALL.iter().map(|a| (*a, hour)).collect(),
+        }
+    }
+
+    /// Sets how long responses of `api` stay valid. A ttl of zero disables
+    /// caching for the api.
+    pub fn with_ttl(mut self, api: Api, ttl: Duration) -> Self {
+        self.ttls.insert(api, ttl);
+        self
+    }
+
+    pub(crate) fn ttl(&self, api: Api) -> Option<Duration> {
+        self.ttls.get(&api).copied().filter(|t| !t.is_zero())
+    }
+
+    pub(crate) async fn get(&self, key: &str) -> Option<HttpResponse> {
+        self.backend.get(key).await
+    }
+
+    pub(crate) async fn put(&self, key: String, response: HttpResponse, ttl: Duration) {
+        let value = CachedResponse {
+            response,
+            expires: SystemTime::now() + ttl,
+        };
+        self.backend.put(key, value).await
+    }
+}
+
+#[test]
+fn memory_cache_evicts_least_recently_used() {
+    use bytes::Bytes;
+    use futures::executor::block_on;
+
+    let cache = MemoryCache::new(2);
+    let entry = |body: &'static str| CachedResponse {
+        response: HttpResponse {
+            status: 200,
+            body: Bytes::from_static(body.as_bytes()),
+        },
+        expires: SystemTime::now() + Duration::from_secs(60),
+    };
+
+    block_on(cache.put("a".into(), entry("a")));
+    block_on(cache.put("b".into(), entry("b")));
+    assert!(block_on(cache.get("a")).is_some());
+    block_on(cache.put("c".into(), entry("c")));
+
+    assert!(block_on(cache.get("a")).is_some());
+    assert!(block_on(cache.get("b")).is_none());
+    assert!(block_on(cache.get("c")).is_some());
+
+    let mut expired = entry("d");
+    expired.expires = SystemTime::now() - Duration::from_secs(1);
+    block_on(cache.put("d".into(), expired));
+    assert!(block_on(cache.get("d")).is_none());
+}
@@ -0,0 +1,1265 @@
+use super::prelude::*;
+use super::*;
+use crate::app_id::AppId;
+use crate::cache::Cache;
+use crate::flight::SingleFlight;
+use crate::limit::{Quota, RateLimiter};
+use crate::response::{
+    raw_response::RawApiResponse, DidYouMean, Image, QueryResult, Sound, Substitution,
+};
+use crate::retry::RetryPolicy;
+use crate::sign::SigningSecret;
+use crate::transport::{HttpResponse, HttpTransport};
+#[cfg(feature = "reqwest")]
+use crate::transport::ReqwestTransport;
+use futures::future::BoxFuture;
+use futures::stream::{self, Stream, StreamExt};
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// The measurement system answers should be given in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitSystem {
+    Metric,
+    Imperial,
+}
+
+impl UnitSystem {
+    /// The value of the `units` parameter the apis expect
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            Self::Metric => "metric",
+            Self::Imperial => "nonmetric",
+        }
+    }
+}
+
+/// The representations of subpods the full results api can return, sent as
+/// the `format` parameter. If none are requested the api returns
+/// [`Format::Plaintext`] and [`Format::Image`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// [`SubPod::plaintext`](crate::response::SubPod::plaintext)
+    Plaintext,
+    /// [`SubPod::img`](crate::response::SubPod::img)
+    Image,
+    /// Clickable regions of the image
+    ImageMap,
+    /// [`SubPod::mathml`](crate::response::SubPod::mathml)
+    MathMl,
+    /// [`SubPod::minput`](crate::response::SubPod::minput)
+    Minput,
+    /// [`SubPod::moutput`](crate::response::SubPod::moutput)
+    Moutput,
+    /// [`SubPod::cell`](crate::response::SubPod::cell)
+    Cell,
+    /// [`Pod::sounds`](crate::response::Pod::sounds) as midi, for pods
+    /// that have audio
+    Sound,
+    /// [`Pod::sounds`](crate::response::Pod::sounds) as wav, for pods
+    /// that have audio
+    Wav,
+}
+
+impl Format {
+    /// The value of the `format` parameter the api expects
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            Self::Plaintext => "plaintext",
+            Self::Image => "image",
+            Self::ImageMap => "imagemap",
+            Self::MathMl => "mathml",
+            Self::Minput => "minput",
+            Self::Moutput => "moutput",
+            Self::Cell => "cell",
+            Self::Sound => "sound",
+            Self::Wav => "wav",
+        }
+    }
+}
+
+/// The wolframalpha apis the client can query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Api {
+    /// The simple api, which answers with an image
+    Simple,
+    /// The short answers api
+    Short,
+    /// The spoken results api
+    Spoken,
+    /// The full results api
+    FullResults,
+}
+
+impl Api {
+    pub const ALL: [Api; 4] = [Api::Simple, Api::Short, Api::Spoken, Api::FullResults];
+
+    /// The path of the api, relative to `api.wolframalpha.com`
+    pub fn path(&self) -> &'static str {
+        match self {
+            Self::Simple => "v1/simple",
+            Self::Short => "v1/result",
+            Self::Spoken => "v1/spoken",
+            Self::FullResults => "v2/query",
+        }
+    }
+}
+
+/// A latitude and longitude in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatLong {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl LatLong {
+    /// Requests with a position that isn't valid fail with
+    /// [`WolframalphaError::InvalidLocation`] without being made
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        LatLong {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Checks that the latitude is between -90 and 90 and the longitude
+    /// between -180 and 180, which also rules out infinities and NaN
+    pub fn is_valid(&self) -> bool {
+        (-90.0..=90.0).contains(&self.latitude) && (-180.0..=180.0).contains(&self.longitude)
+    }
+}
+
+/// Where the question is asked from, for questions whose answer depends on
+/// it such as `weather` or `what time is it`. If no location is given the
+/// apis use the location of the ip address the request comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Location {
+    /// The location of an ip address, sent as the `ip` parameter
+    Ip(IpAddr),
+    /// A position, sent as the `latlong` parameter
+    LatLong(LatLong),
+    /// A place name such as `Boston, MA`, sent as the `location` parameter
+    Named(String),
+}
+
+impl Location {
+    /// The name and value of the parameter the apis expect
+    pub fn as_param(&self) -> (&'static str, String) {
+        match self {
+            Self::Ip(ip) => ("ip", ip.to_string()),
+            Self::LatLong(l) => ("latlong", format!("{},{}", l.latitude, l.longitude)),
+            Self::Named(name) => ("location", name.clone()),
+        }
+    }
+}
+
+/// Options that apply to a single request, see [`Client::query_with`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestOptions {
+    /// Ignore cached responses and make the request, if the client has a
+    /// [`Cache`]. The response is still cached.
+    pub bypass_cache: bool,
+    /// Overrides the location set with [`Client::with_location`]
+    pub location: Option<Location>,
+    /// Overrides the formats set with [`Client::with_formats`]
+    pub formats: Option<Vec<Format>>,
+}
+
+/// Options shared by the async and blocking clients
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub app_id: AppId,
+    pub did_you_mean_threshold: Option<f32>,
+    pub reinterpret: bool,
+    pub units: Option<UnitSystem>,
+    pub signing_secret: Option<SigningSecret>,
+    pub location: Option<Location>,
+    pub formats: Vec<Format>,
+    pub rate_limiter: Option<RateLimiter>,
+    pub quota: Option<Quota>,
+    pub retry: Option<RetryPolicy>,
+}
+
+/// How a client performs the steps of [`Config::get`]: the async client
+/// awaits its transport, the blocking one does the work before returning a
+/// ready future.
+pub(crate) trait RequestIo {
+    fn get(&self, url: &str) -> BoxFuture<'_, Result<HttpResponse, Box<dyn Error + Send + Sync>>>;
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()>;
+
+    /// Counts a request against `quota`
+    fn count<'a>(
+        &'a self,
+        quota: &'a Quota,
+    ) -> BoxFuture<'a, Result<Result<(), WolframalphaError>, Box<dyn Error + Send + Sync>>>;
+}
+
+impl Config {
+    pub fn new(app_id: AppId) -> Self {
+        Config {
+            app_id,
+            did_you_mean_threshold: None,
+            reinterpret: false,
+            units: None,
+            signing_secret: None,
+            location: None,
+            formats: vec![],
+            rate_limiter: None,
+            quota: None,
+            retry: None,
+        }
+    }
+
+    /// Performs a GET request, retrying it according to the retry policy.
+    /// For api calls every attempt is `metered`: counted against the quota
+    /// and subject to the rate limit.
+    pub async fn get(
+        &self,
+        io: &impl RequestIo,
+        url: &str,
+        metered: bool,
+    ) -> Result<Result<HttpResponse, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        let mut attempt = 1;
+        loop {
+            if metered {
+                if let Some(quota) = &self.quota {
+                    if let Err(e) = io.count(quota).await? {
+                        return Ok(Err(e));
+                    }
+                }
+                if let Some(limiter) = &self.rate_limiter {
+                    let wait = limiter.reserve();
+                    if !wait.is_zero() {
+                        io.sleep(wait).await;
+                    }
+                }
+            }
+
+            let result = io.get(url).await;
+
+            let delay = self.retry.as_ref().and_then(|p| match &result {
+                Ok(r) => p.retry_delay(attempt, Ok(r.status)),
+                Err(e) => p.retry_delay(attempt, Err(&**e)),
+            });
+            match delay {
+                Some(d) => io.sleep(d).await,
+                None => return Ok(Ok(result?)),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// The suggestion to ask instead of the question that got `result`, if
+    /// did you mean retries are on and it scores high enough
+    pub fn did_you_mean<'a>(&self, result: &'a QueryResult) -> Option<&'a DidYouMean> {
+        self.did_you_mean_threshold
+            .and_then(|t| result.best_did_you_mean(t))
+    }
+
+    /// Marks `retried` as the answer to `suggestion` instead of `question`
+    pub fn substitute(
+        question: &str,
+        suggestion: &DidYouMean,
+        mut retried: QueryResult,
+    ) -> QueryResult {
+        retried.substitution = Some(Substitution {
+            original: question.to_string(),
+            suggestion: suggestion.clone(),
+        });
+        retried
+    }
+
+    /// Url for asking `api` `question`
+    pub fn url(
+        &self,
+        api: Api,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<String, WolframalphaError> {
+        if !self.app_id.is_valid() {
+            return Err(WolframalphaError::InvalidAppId);
+        }
+        encoding::validate_question(question)?;
+
+        let mut query = encoding::QueryBuilder::new();
+        query.param("appid", self.app_id.expose());
+        match api {
+            Api::FullResults => {
+                query.param("input", question).param("output", "json");
+                if self.reinterpret {
+                    query.param("reinterpret", "true");
+                }
+                let formats = options.formats.as_ref().unwrap_or(&self.formats);
+                if !formats.is_empty() {
+                    let formats: Vec<_> = formats.iter().map(Format::as_param).collect();
+                    query.param("format", &formats.join(","));
+                }
+            }
+            _ => {
+                query.param("i", question);
+            }
+        }
+        if let Some(units) = self.units {
+            query.param("units", units.as_param());
+        }
+        if let Some(location) = options.location.as_ref().or(self.location.as_ref()) {
+            if matches!(location, Location::LatLong(l) if !l.is_valid()) {
+                return Err(WolframalphaError::InvalidLocation);
+            }
+            let (name, value) = location.as_param();
+            query.param(name, &value);
+        }
+        if let Some(secret) = &self.signing_secret {
+            let sig = sign::sign(secret.expose(), query.params());
+            query.param("sig", &sig);
+        }
+
+        Ok(format!(
+            "http://api.wolframalpha.com/{}?{}",
+            api.path(),
+            query.finish()
+        ))
+    }
+}
+
+/// Performs the steps of [`Config::get`] through an [`HttpTransport`]
+struct AsyncIo<'a, T>(&'a T);
+
+impl<T: HttpTransport> RequestIo for AsyncIo<'_, T> {
+    fn get(&self, url: &str) -> BoxFuture<'_, Result<HttpResponse, Box<dyn Error + Send + Sync>>> {
+        self.0.get(url.to_string())
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        self.0.sleep(duration)
+    }
+
+    fn count<'a>(
+        &'a self,
+        quota: &'a Quota,
+    ) -> BoxFuture<'a, Result<Result<(), WolframalphaError>, Box<dyn Error + Send + Sync>>> {
+        Box::pin(quota.acquire_async())
+    }
+}
+
+/// The result of one question of [`Client::query_batch`]
+type QueryBatchItem = Result<Result<QueryResult, WolframalphaError>, Box<dyn Error + Send + Sync>>;
+
+/// Collapses whitespace so trivially different questions share cache entries
+fn normalize_question(question: &str) -> String {
+    question.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A reusable handle to the wolframalpha api. Holds the AppID and the
+/// transport that performs the http requests, which by default is a
+/// `reqwest` connection pool shared between requests.
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone)]
+pub struct Client<T = ReqwestTransport> {
+    config: Config,
+    transport: T,
+    cache: Option<Cache>,
+    single_flight: Option<SingleFlight>,
+}
+
+/// A reusable handle to the wolframalpha api. Holds the AppID and the
+/// transport that performs the http requests. Without the `reqwest` feature
+/// there is no default transport.
+#[cfg(not(feature = "reqwest"))]
+#[derive(Debug, Clone)]
+pub struct Client<T> {
+    config: Config,
+    transport: T,
+    cache: Option<Cache>,
+    single_flight: Option<SingleFlight>,
+}
+
+#[cfg(feature = "reqwest")]
+impl Client {
+    /// Creates a new client
+    ///
+    /// # Arguments
+    ///
+    /// * `app_id` - The AppID of your wolframalpha application
+    pub fn new(app_id: impl Into<AppId>) -> Self {
+        Self::with_transport(app_id, ReqwestTransport::new())
+    }
+}
+
+impl<T: HttpTransport> Client<T> {
+    /// Creates a new client that performs its requests through `transport`
+    ///
+    /// # Arguments
+    ///
+    /// * `app_id` - The AppID of your wolframalpha application
+    /// * `transport` - The http implementation to use
+    pub fn with_transport(app_id: impl Into<AppId>, transport: T) -> Self {
+        Client {
+            config: Config::new(app_id.into()),
+            transport,
+            cache: None,
+            single_flight: Some(SingleFlight::default()),
+        }
+    }
+
+    /// Makes [`Client::query`] reissue questions that were not understood
+    /// with the best "did you mean" suggestion, as long as its score is at
+    /// least `min_score`. Results of reissued queries have
+    /// [`QueryResult::substitution`] set.
+    pub fn with_did_you_mean_retry(mut self, min_score: f32) -> Self {
+        self.config.did_you_mean_threshold = Some(min_score);
+        self
+    }
+
+    /// Allows the full results api to answer a closely related question when
+    /// it does not understand the one asked, see
+    /// [`QueryResult::reinterpretation`]
+    pub fn with_reinterpret(mut self, reinterpret: bool) -> Self {
+        self.config.reinterpret = reinterpret;
+        self
+    }
+
+    /// Sets the measurement system of the answers of all apis. If this is
+    /// not set the api picks one based on the location of the caller.
+    pub fn with_units(mut self, units: UnitSystem) -> Self {
+        self.config.units = Some(units);
+        self
+    }
+
+    /// Signs every request with `secret`, for AppIDs that require signed
+    /// requests. See [`sign`] for how the signature is computed.
+    pub fn with_signing_secret(mut self, secret: impl Into<String>) -> Self {
+        self.config.signing_secret = Some(SigningSecret::new(secret.into()));
+        self
+    }
+
+    /// Sets the subpod formats the full results api returns. Can be
+    /// overridden per request with [`RequestOptions::formats`].
+    pub fn with_formats(mut self, formats: &[Format]) -> Self {
+        self.config.formats = formats.to_vec();
+        self
+    }
+
+    /// Sets the location questions are asked from, for all apis. Can be
+    /// overridden per request with [`RequestOptions::location`].
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.config.location = Some(location);
+        self
+    }
+
+    /// Caches successful responses of the apis, so asking the same question
+    /// again doesn't use up api calls
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Limits how fast api calls are made. Calls over the limit wait until
+    /// the limiter allows them, using [`HttpTransport::sleep`].
+    pub fn with_rate_limit(mut self, limiter: RateLimiter) -> Self {
+        self.config.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Counts api calls against a monthly budget. Once it is used up calls
+    /// fail with [`WolframalphaError::QuotaExceeded`] without being made.
+    /// Answers served from the cache are not counted.
+    pub fn with_quota(mut self, quota: Quota) -> Self {
+        self.config.quota = Some(quota);
+        self
+    }
+
+    /// Retries requests that fail with a server error or a network problem
+    /// that is likely to go away, according to `policy`. This applies to
+    /// every api and to image and sound downloads. Every attempt of an api
+    /// call counts against the quota and waits for the rate limit. By
+    /// default requests are not retried.
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.config.retry = Some(policy);
+        self
+    }
+
+    /// Controls whether identical requests made at the same time, through
+    /// this client or its clones, are coalesced into one request whose
+    /// response all of them receive. Enabled by default.
+    pub fn with_deduplication(mut self, enabled: bool) -> Self {
+        self.single_flight = if enabled {
+            Some(SingleFlight::default())
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Performs a GET request through the transport, see [`Config::get`]
+    async fn get(
+        &self,
+        url: String,
+        metered: bool,
+    ) -> Result<Result<HttpResponse, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.config
+            .get(&AsyncIo(&self.transport), &url, metered)
+            .await
+    }
+
+    /// Makes an api call, subject to the quota, rate limit and retry policy
+    async fn send(
+        &self,
+        url: String,
+    ) -> Result<Result<HttpResponse, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.get(url, true).await
+    }
+
+    /// Asks `api` `question`, going through the cache if there is one
+    async fn request(
+        &self,
+        api: Api,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<HttpResponse, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        let url = match self.config.url(api, question, options) {
+            Ok(u) => u,
+            Err(e) => return Ok(Err(e)),
+        };
+        let key = encoding::redact_url(&self.config.url(
+            api,
+            &normalize_question(question),
+            options,
+        )?);
+
+        let cache = self
+            .cache
+            .as_ref()
+            .and_then(|c| c.ttl(api).map(|ttl| (c, ttl)));
+
+        if let Some((cache, _)) = cache {
+            if !options.bypass_cache {
+                if let Some(response) = cache.get(&key).await {
+                    return Ok(Ok(response));
+                }
+            }
+        }
+
+        let response = match &self.single_flight {
+            Some(flights) => flights.run(&key, || self.send(url)).await?,
+            None => self.send(url).await?,
+        };
+        let response = match response {
+            Ok(r) => r,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        if let Some((cache, ttl)) = cache {
+            if response.status == 200 {
+                cache.put(key, response.clone(), ttl).await;
+            }
+        }
+
+        Ok(Ok(response))
+    }
+
+    /// Requests one of the `v1` apis, which answer 501 to questions they
+    /// can't answer
+    async fn retrieve_v1(
+        &self,
+        api: Api,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<HttpResponse, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        if question.trim() == "" {
+            return Ok(Err(WolframalphaError::InvalidQuestion));
+        }
+
+        let response = match self.request(api, question, options).await? {
+            Ok(r) => r,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        if response.status == 501 {
+            return Ok(Err(WolframalphaError::InvalidQuestion));
+        }
+
+        Ok(Ok(response.error_for_status()?))
+    }
+
+    async fn retrieve_text(
+        &self,
+        api: Api,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<String, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        Ok(match self.retrieve_v1(api, question, options).await? {
+            Ok(response) => Ok(String::from_utf8(response.body.to_vec())?),
+            Err(e) => Err(e),
+        })
+    }
+
+    /// Performs a short answers api request, returning a short plaintext
+    /// answer such as `"65.3 million people"`
+    pub async fn short_answer(
+        &self,
+        question: &str,
+    ) -> Result<Result<String, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.short_answer_with(question, &RequestOptions::default())
+            .await
+    }
+
+    /// [`Client::short_answer`] with options for this request only
+    pub async fn short_answer_with(
+        &self,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<String, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.retrieve_text(Api::Short, question, options).await
+    }
+
+    /// Performs a spoken results api request, returning a full sentence
+    /// answer meant to be read aloud
+    pub async fn spoken_answer(
+        &self,
+        question: &str,
+    ) -> Result<Result<String, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.spoken_answer_with(question, &RequestOptions::default())
+            .await
+    }
+
+    /// [`Client::spoken_answer`] with options for this request only
+    pub async fn spoken_answer_with(
+        &self,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<String, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.retrieve_text(Api::Spoken, question, options).await
+    }
+
+    /// Performs a full results api request, returning the json response
+    /// as is, without parsing it. Did you mean suggestions are not retried.
+    pub async fn query_raw(
+        &self,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<Bytes, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        if question.trim() == "" {
+            return Ok(Err(WolframalphaError::InvalidQuestion));
+        }
+
+        Ok(
+            match self.request(Api::FullResults, question, options).await? {
+                Ok(r) => Ok(r.error_for_status()?.body),
+                Err(e) => Err(e),
+            },
+        )
+    }
+
+    async fn query_once(
+        &self,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<QueryResult, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        let body = match self.query_raw(question, options).await? {
+            Ok(b) => b,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        let raw: RawApiResponse = serde_json::from_slice(&body)?;
+
+        Ok(Ok(raw.into()))
+    }
+
+    /// Performs a full results api request
+    pub async fn query(
+        &self,
+        question: &str,
+    ) -> Result<Result<QueryResult, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.query_with(question, &RequestOptions::default()).await
+    }
+
+    /// [`Client::query`] with options for this request only
+    pub async fn query_with(
+        &self,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<QueryResult, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        if question.trim() == "" {
+            return Ok(Err(WolframalphaError::InvalidQuestion));
+        }
+
+        let result = match self.query_once(question, options).await? {
+            Ok(r) => r,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        if let Some(suggestion) = self.config.did_you_mean(&result) {
+            let retried = match self.query_once(&suggestion.val, options).await? {
+                Ok(r) => r,
+                Err(e) => return Ok(Err(e)),
+            };
+            return Ok(Ok(Config::substitute(question, suggestion, retried)));
+        }
+
+        Ok(Ok(result))
+    }
+
+    /// Performs a full results api request for every question in
+    /// `questions`, with at most `concurrency` requests in flight at once.
+    /// The results are in the same order as the questions, and a failed
+    /// question doesn't affect the others. Requests go through the cache,
+    /// quota and rate limit like those of [`Client::query`].
+    pub async fn query_batch<I>(&self, questions: I, concurrency: usize) -> Vec<QueryBatchItem>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        stream::iter(questions)
+            .map(|q| async move { self.query(q.as_ref()).await })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Like [`Client::query_batch`], but yields the results as soon as they
+    /// arrive, together with the index of their question
+    pub fn query_stream<I>(
+        &self,
+        questions: I,
+        concurrency: usize,
+    ) -> impl Stream<Item = (usize, QueryBatchItem)> + '_
+    where
+        I: IntoIterator,
+        I::IntoIter: 'static,
+        I::Item: AsRef<str> + 'static,
+    {
+        stream::iter(questions.into_iter().enumerate())
+            .map(move |(i, q)| async move { (i, self.query(q.as_ref()).await) })
+            .buffer_unordered(concurrency.max(1))
+    }
+
+    /// Performs a simple api request, returning the raw bytes of the image
+    pub async fn retrieve_bytes(
+        &self,
+        question: &str,
+    ) -> Result<Result<Bytes, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        self.retrieve_bytes_with(question, &RequestOptions::default())
+            .await
+    }
+
+    /// [`Client::retrieve_bytes`] with options for this request only
+    pub async fn retrieve_bytes_with(
+        &self,
+        question: &str,
+        options: &RequestOptions,
+    ) -> Result<Result<Bytes, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        Ok(self
+            .retrieve_v1(Api::Simple, question, options)
+            .await?
+            .map(|response| response.body))
+    }
+
+    async fn fetch(&self, url: &str) -> Result<Bytes, Box<dyn Error + Send + Sync>> {
+        match self.get(url.to_string(), false).await? {
+            Ok(response) => Ok(response.error_for_status()?.body),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Downloads the image a response points to, such as a subpod image
+    pub async fn fetch_image_bytes(
+        &self,
+        image: &Image,
+    ) -> Result<Bytes, Box<dyn Error + Send + Sync>> {
+        self.fetch(&image.source).await
+    }
+
+    /// Downloads the audio of a pod sound, see [`Sound::sound_type`] for its
+    /// format. Pods only have sounds if they were requested with
+    /// [`Format::Sound`] or [`Format::Wav`].
+    pub async fn fetch_sound(&self, sound: &Sound) -> Result<Bytes, Box<dyn Error + Send + Sync>> {
+        self.fetch(&sound.url).await
+    }
+
+    /// Downloads the image of every subpod in `result`, with at most
+    /// `parallelism` downloads in flight at once. The outer vector has one
+    /// entry per pod and the inner vectors one entry per subpod that has an
+    /// image, in the same order as in `result`.
+    pub async fn fetch_subpod_image_bytes(
+        &self,
+        result: &QueryResult,
+        parallelism: usize,
+    ) -> Result<Vec<Vec<Bytes>>, Box<dyn Error + Send + Sync>> {
+        let images = result.pods.iter().enumerate().flat_map(|(i, p)| {
+            p.subpods
+                .iter()
+                .filter_map(move |s| s.img.as_ref().map(|img| (i, img)))
+        });
+
+        let fetched: Vec<_> = stream::iter(images)
+            .map(|(i, img)| async move { (i, self.fetch_image_bytes(img).await) })
+            .buffered(parallelism.max(1))
+            .collect()
+            .await;
+
+        let mut res: Vec<Vec<Bytes>> = vec![vec![]; result.pods.len()];
+        for (i, bytes) in fetched {
+            res[i].push(bytes?);
+        }
+
+        Ok(res)
+    }
+}
+
+#[test]
+fn client_with_transport() {
+    use futures::future::BoxFuture;
+    use std::sync::Mutex;
+
+    struct Fake(Mutex<Vec<String>>);
+
+    impl HttpTransport for Fake {
+        fn get(
+            &self,
+            url: String,
+        ) -> BoxFuture<'_, Result<HttpResponse, Box<dyn Error + Send + Sync>>> {
+            let status = if url.contains("/v1/result") { 200 } else { 501 };
+            self.0.lock().unwrap().push(url);
+            Box::pin(async move {
+                Ok(HttpResponse {
+                    status,
+                    body: Bytes::from_static(b"65.3 million people"),
+                })
+            })
+        }
+    }
+
+    let client =
+        Client::with_transport("APPID", Fake(Mutex::new(vec![]))).with_units(UnitSystem::Imperial);
+
+    let answer = futures::executor::block_on(client.short_answer("population of france")).unwrap();
+    assert_eq!(answer.unwrap(), "65.3 million people");
+
+    let answer = futures::executor::block_on(client.spoken_answer("asdfgh")).unwrap();
+    assert!(matches!(answer, Err(WolframalphaError::InvalidQuestion)));
+
+    assert_eq!(
+        client.transport.0.lock().unwrap()[0],
+        "http://api.wolframalpha.com/v1/result?appid=APPID&i=population%20of%20france&units=nonmetric"
+    );
+}
+
+#[test]
+fn client_with_cache() {
+    use crate::testing::{json, Matcher, MockTransport};
+    use crate::MemoryCache;
+    use futures::executor::block_on;
+
+    let transport = MockTransport::new().on(
+        Matcher::api("v2/query"),
+        json(crate::testing::fixtures::POPULATION_FRANCE),
+    );
+    let client = Client::with_transport("APPID", transport.clone())
+        .with_cache(Cache::new(MemoryCache::new(16)));
+
+    let first = block_on(client.query("population  france"))
+        .unwrap()
+        .unwrap();
+    let second = block_on(client.query("population france"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(first, second);
+    assert_eq!(transport.requests().len(), 1);
+
+    let options = RequestOptions {
+        bypass_cache: true,
+        ..Default::default()
+    };
+    block_on(client.query_with("population france", &options))
+        .unwrap()
+        .unwrap();
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn client_with_quota() {
+    use crate::testing::{json, Matcher, MockTransport};
+    use futures::executor::block_on;
+
+    let transport = MockTransport::new().on(
+        Matcher::api("v2/query"),
+        json(crate::testing::fixtures::POPULATION_FRANCE),
+    );
+    let client = Client::with_transport("APPID", transport.clone()).with_quota(Quota::new(1));
+
+    assert!(block_on(client.query("population france")).unwrap().is_ok());
+    assert!(matches!(
+        block_on(client.query("population france")).unwrap(),
+        Err(WolframalphaError::QuotaExceeded)
+    ));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn client_with_rate_limit() {
+    use crate::testing::{json, Matcher, MockTransport};
+    use futures::executor::block_on;
+    use std::time::{Duration, Instant};
+
+    let transport = MockTransport::new().on(
+        Matcher::api("v2/query"),
+        json(crate::testing::fixtures::POPULATION_FRANCE),
+    );
+    let client = Client::with_transport("APPID", transport.clone())
+        .with_rate_limit(RateLimiter::new(1, Duration::from_millis(50)));
+
+    // waiting doesn't need a tokio runtime
+    let start = Instant::now();
+    assert!(block_on(client.query("population france")).unwrap().is_ok());
+    assert!(block_on(client.query("population france")).unwrap().is_ok());
+    assert!(start.elapsed() >= Duration::from_millis(40));
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn client_with_retry() {
+    use futures::executor::block_on;
+    use futures::future::BoxFuture;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Fails with 503 until the third attempt
+    struct Flaky(AtomicUsize);
+
+    impl HttpTransport for Flaky {
+        fn get(
+            &self,
+            _url: String,
+        ) -> BoxFuture<'_, Result<HttpResponse, Box<dyn Error + Send + Sync>>> {
+            let attempt = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            Box::pin(async move {
+                Ok(HttpResponse {
+                    status: if attempt < 3 { 503 } else { 200 },
+                    body: Bytes::from_static(b"65.3 million people"),
+                })
+            })
+        }
+    }
+
+    let client =
+        Client::with_transport("APPID", Flaky(AtomicUsize::new(0))).with_retry(RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..Default::default()
+        });
+    let answer = block_on(client.short_answer("population of france")).unwrap();
+    assert_eq!(answer.unwrap(), "65.3 million people");
+    assert_eq!(client.transport.0.load(Ordering::SeqCst), 3);
+
+    let client =
+        Client::with_transport("APPID", Flaky(AtomicUsize::new(0))).with_retry(RetryPolicy {
+            max_attempts: 2,
+            initial_backoff: Duration::from_millis(1),
+            ..Default::default()
+        });
+    assert!(block_on(client.short_answer("population of france")).is_err());
+
+    // every attempt is counted, so the third one is refused
+    let client = Client::with_transport("APPID", Flaky(AtomicUsize::new(0)))
+        .with_retry(RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..Default::default()
+        })
+        .with_quota(Quota::new(2));
+    assert!(matches!(
+        block_on(client.short_answer("population of france")).unwrap(),
+        Err(WolframalphaError::QuotaExceeded)
+    ));
+    assert_eq!(client.transport.0.load(Ordering::SeqCst), 2);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn client_deduplicates_concurrent_requests() {
+    use futures::future::BoxFuture;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    struct Slow(AtomicUsize);
+
+    impl HttpTransport for Slow {
+        fn get(
+            &self,
+            _url: String,
+        ) -> BoxFuture<'_, Result<HttpResponse, Box<dyn Error + Send + Sync>>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok(HttpResponse {
+                    status: 200,
+                    body: Bytes::from_static(b"65.3 million people"),
+                })
+            })
+        }
+    }
+
+    let client = Client::with_transport("APPID", Slow(AtomicUsize::new(0)));
+
+    let answers = futures::future::join_all(
+        [
+            "population of france",
+            "population  of france",
+            "population of sweden",
+        ]
+        .iter()
+        .map(|q| client.short_answer(q)),
+    )
+    .await;
+    for answer in answers {
+        assert_eq!(answer.unwrap().unwrap(), "65.3 million people");
+    }
+    assert_eq!(client.transport.0.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn client_query_batch() {
+    use crate::testing::{fixtures, json, status, Matcher, MockTransport};
+    use futures::executor::block_on;
+
+    let transport = MockTransport::new()
+        .on(
+            Matcher::api("v2/query").param("input", "population france"),
+            json(fixtures::POPULATION_FRANCE),
+        )
+        .on(
+            Matcher::api("v2/query").param("input", "broken"),
+            status(500),
+        );
+    let client = Client::with_transport("APPID", transport);
+
+    let questions = ["population france", "broken", "", "population france"];
+    let results = block_on(client.query_batch(questions, 2));
+    assert_eq!(results.len(), 4);
+    assert!(results[0].as_ref().unwrap().is_ok());
+    assert!(results[1].is_err());
+    assert!(matches!(
+        results[2].as_ref().unwrap(),
+        Err(WolframalphaError::InvalidQuestion)
+    ));
+    assert!(results[3].as_ref().unwrap().is_ok());
+
+    let mut streamed: Vec<_> = block_on(client.query_stream(questions, 2).collect::<Vec<_>>())
+        .into_iter()
+        .map(|(i, r)| (i, r.is_ok()))
+        .collect();
+    streamed.sort_unstable();
+    assert_eq!(streamed, vec![(0, true), (1, false), (2, true), (3, true)]);
+}
+
+#[test]
+fn client_signs_requests() {
+    let mut config = Config::new("DEMO".into());
+    config.signing_secret = Some(SigningSecret::new("SECRET".to_string()));
+
+    assert_eq!(
+        config
+            .url(Api::FullResults, "pi", &RequestOptions::default())
+            .unwrap(),
+        "http://api.wolframalpha.com/v2/query?appid=DEMO&input=pi&output=json\
+         &sig=8E672BAF5DA5BE83E4C960F173083F41"
+    );
+}
+
+#[test]
+fn client_location_params() {
+    use crate::testing::{status, Matcher, MockTransport};
+    use futures::executor::block_on;
+
+    let transport = MockTransport::new().on(Matcher::any(), status(501));
+    let client = Client::with_transport("APPID", transport.clone())
+        .with_location(Location::LatLong(LatLong::new(40.11, -88.24)));
+
+    let _ = block_on(client.short_answer("weather"));
+    let options = RequestOptions {
+        location: Some(Location::Named("Boston, MA".to_string())),
+        ..Default::default()
+    };
+    let _ = block_on(client.spoken_answer_with("weather", &options));
+    let options = RequestOptions {
+        location: Some(Location::Ip("192.0.2.1".parse().unwrap())),
+        ..Default::default()
+    };
+    let _ = block_on(client.query_with("weather", &options));
+
+    let requests = transport.requests();
+    assert!(requests[0].ends_with("&latlong=40.11%2C-88.24"));
+    assert!(requests[1].ends_with("&location=Boston%2C%20MA"));
+    assert!(Matcher::api("v2/query")
+        .param("ip", "192.0.2.1")
+        .matches(&requests[2]));
+    let options = RequestOptions {
+        location: Some(Location::LatLong(LatLong::new(f64::NAN, 1e300))),
+        ..Default::default()
+    };
+    assert!(matches!(
+        block_on(client.short_answer_with("weather", &options)).unwrap(),
+        Err(WolframalphaError::InvalidLocation)
+    ));
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[test]
+fn client_format_param() {
+    use crate::testing::{status, Matcher, MockTransport};
+    use futures::executor::block_on;
+
+    let transport = MockTransport::new().on(Matcher::any(), status(501));
+    let client = Client::with_transport("APPID", transport.clone())
+        .with_formats(&[Format::MathMl, Format::Plaintext]);
+
+    let _ = block_on(client.query("pi"));
+    let options = RequestOptions {
+        formats: Some(vec![Format::Minput, Format::Moutput]),
+        ..Default::default()
+    };
+    let _ = block_on(client.query_with("pi", &options));
+    let _ = block_on(client.short_answer("pi"));
+    let options = RequestOptions {
+        formats: Some(vec![Format::Cell, Format::Sound, Format::Wav]),
+        ..Default::default()
+    };
+    let _ = block_on(client.query_with("C major", &options));
+
+    let requests = transport.requests();
+    assert!(Matcher::api("v2/query")
+        .param("format", "mathml,plaintext")
+        .matches(&requests[0]));
+    assert!(Matcher::api("v2/query")
+        .param("format", "minput,moutput")
+        .matches(&requests[1]));
+    assert!(!requests[2].contains("format"));
+    assert!(Matcher::api("v2/query")
+        .param("format", "cell,sound,wav")
+        .matches(&requests[3]));
+}
+
+#[test]
+fn client_reinterpret_param() {
+    use crate::testing::{status, Matcher, MockTransport};
+    use futures::executor::block_on;
+
+    let transport = MockTransport::new().on(Matcher::any(), status(501));
+    let client = Client::with_transport("APPID", transport.clone()).with_reinterpret(true);
+
+    let _ = block_on(client.query("weather"));
+    let _ = block_on(client.short_answer("weather"));
+
+    let requests = transport.requests();
+    assert!(Matcher::api("v2/query")
+        .param("reinterpret", "true")
+        .matches(&requests[0]));
+    // only the full results api knows the parameter
+    assert!(!requests[1].contains("reinterpret"));
+}
+
+#[test]
+fn client_errors_hide_app_id() {
+    use crate::testing::{status, Matcher, MockTransport};
+    use futures::executor::block_on;
+
+    let transport = MockTransport::new().on(Matcher::any(), status(500));
+    let client = Client::with_transport("SECRET-APPID", transport);
+
+    let e = block_on(client.short_answer("weather")).unwrap_err();
+    assert_eq!(e.to_string(), "http status 500");
+    let e = block_on(client.query("weather")).unwrap_err();
+    assert!(!e.to_string().contains("SECRET-APPID"));
+    // requests without a mock response
+    let client = Client::with_transport("SECRET-APPID", MockTransport::new());
+    let e = block_on(client.short_answer("weather")).unwrap_err();
+    assert!(e.to_string().starts_with("no mock response for"));
+    assert!(!e.to_string().contains("SECRET-APPID"));
+}
+
+#[test]
+fn client_did_you_mean_retry() {
+    use crate::testing::{fixtures, json, Matcher, MockTransport};
+    use futures::executor::block_on;
+
+    let transport = MockTransport::new()
+        .on(
+            Matcher::api("v2/query").param("input", "wehter in bostn"),
+            json(fixtures::DID_YOU_MEAN),
+        )
+        .on(
+            Matcher::api("v2/query").param("input", "weather in"),
+            json(fixtures::POPULATION_FRANCE),
+        );
+
+    let client = Client::with_transport("APPID", transport.clone()).with_did_you_mean_retry(0.4);
+    let result = block_on(client.query("wehter in bostn")).unwrap().unwrap();
+    assert!(result.success);
+    let substitution = result.substitution.unwrap();
+    assert_eq!(substitution.original, "wehter in bostn");
+    assert_eq!(substitution.suggestion.val, "weather in");
+    assert_eq!(transport.requests().len(), 2);
+
+    // the best suggestion scores 0.415, below the threshold
+    let client = Client::with_transport("APPID", transport.clone()).with_did_you_mean_retry(0.5);
+    let result = block_on(client.query("wehter in bostn")).unwrap().unwrap();
+    assert!(!result.success);
+    assert_eq!(result.substitution, None);
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[test]
+fn client_fetch_subpod_image_bytes() {
+    use crate::testing::{bytes, fixtures, parse_query_result, parse_url, status};
+    use crate::testing::{Matcher, MockTransport};
+    use futures::executor::block_on;
+
+    let mut result = parse_query_result(fixtures::POPULATION_FRANCE).unwrap();
+    result.pods.truncate(2);
+    let mut without_image = result.pods[1].subpods[0].clone();
+    without_image.img = None;
+    result.pods[1].subpods.push(without_image);
+
+    let image_api = |pod: usize| {
+        let source = &result.pods[pod].subpods[0].img.as_ref().unwrap().source;
+        Matcher::api(parse_url(source).0)
+    };
+    let transport = MockTransport::new()
+        .on(image_api(0), bytes("first"))
+        .on(image_api(1), bytes("second"));
+    let client = Client::with_transport("APPID", transport.clone());
+
+    let images = block_on(client.fetch_subpod_image_bytes(&result, 2)).unwrap();
+    assert_eq!(images, vec![vec!["first"], vec!["second"]]);
+    assert_eq!(transport.requests().len(), 2);
+
+    let transport = MockTransport::new().on(Matcher::any(), status(404));
+    let client = Client::with_transport("APPID", transport);
+    let e = block_on(client.fetch_subpod_image_bytes(&result, 2)).unwrap_err();
+    assert_eq!(e.to_string(), "http status 404");
+}
+
+#[test]
+fn client_fetch_sound() {
+    use crate::testing::{bytes, status, Matcher, MockTransport};
+    use futures::executor::block_on;
+
+    let sound = Sound {
+        url: "https://www6b3.wolframalpha.com/Calculate/MSP/MSP3571?MSPStoreType=audio/midi&s=19"
+            .to_string(),
+        sound_type: "audio/midi".to_string(),
+    };
+
+    let transport = MockTransport::new()
+        .on(Matcher::api("Calculate/MSP/MSP3571"), bytes("MThd"))
+        .on(Matcher::any(), status(404));
+    let client = Client::with_transport("APPID", transport.clone());
+    assert_eq!(block_on(client.fetch_sound(&sound)).unwrap(), "MThd");
+    assert_eq!(transport.requests(), vec![sound.url.clone()]);
+
+    let missing = Sound {
+        url: "https://www6b3.wolframalpha.com/Calculate/MSP/MSP3572".to_string(),
+        ..sound
+    };
+    assert!(block_on(client.fetch_sound(&missing)).is_err());
+}
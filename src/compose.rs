@@ -0,0 +1,243 @@
+use crate::response::QueryResult;
+use ab_glyph::{point, Font, PxScale, ScaleFont};
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+
+pub use ab_glyph::FontArc;
+
+/// Controls the layout of [`compose_result`]
+#[derive(Debug, Clone)]
+pub struct ComposeOptions {
+    /// Width of the composed image in pixels. Subpod images wider than this
+    /// (minus the spacing on both sides) are scaled down to fit.
+    pub width: u32,
+    pub background: Rgba<u8>,
+    /// Color of the pod titles
+    pub foreground: Rgba<u8>,
+    /// Space in pixels around the edges and between pods
+    pub spacing: u32,
+    /// Font used for the pod titles. Titles are left out if this is `None`,
+    /// as no font is bundled with this crate.
+    pub font: Option<FontArc>,
+    /// Pixel height of the pod titles
+    pub title_size: f32,
+}
+
+impl ComposeOptions {
+    /// The default layout, with the pod titles drawn in `font`
+    pub fn new(font: FontArc) -> Self {
+        ComposeOptions {
+            font: Some(font),
+            ..Default::default()
+        }
+    }
+}
+
+/// The default layout has **no font, so the pod titles are left out** of the
+/// composed image. Use [`ComposeOptions::new`] to draw them.
+impl Default for ComposeOptions {
+    fn default() -> Self {
+        ComposeOptions {
+            width: 500,
+            background: Rgba([255, 255, 255, 255]),
+            foreground: Rgba([0, 0, 0, 255]),
+            spacing: 10,
+            font: None,
+            title_size: 16.0,
+        }
+    }
+}
+
+enum Item<'a> {
+    Title(&'a str),
+    Image(DynamicImage),
+}
+
+fn draw_text(
+    canvas: &mut RgbaImage,
+    font: &FontArc,
+    size: f32,
+    color: Rgba<u8>,
+    x: u32,
+    y: u32,
+    text: &str,
+) {
+    let scaled = font.as_scaled(PxScale::from(size));
+    let baseline = y as f32 + scaled.ascent();
+    let mut caret = x as f32;
+    let mut last = None;
+
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(prev) = last {
+            caret += scaled.kern(prev, id);
+        }
+        let glyph = id.with_scale_and_position(size, point(caret, baseline));
+        caret += scaled.h_advance(id);
+        last = Some(id);
+
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i64 + gx as i64;
+                let py = bounds.min.y as i64 + gy as i64;
+                if px < 0 || py < 0 || px >= canvas.width() as i64 || py >= canvas.height() as i64 {
+                    return;
+                }
+                let pixel = canvas.get_pixel_mut(px as u32, py as u32);
+                for i in 0..4 {
+                    let blended = pixel[i] as f32 * (1.0 - coverage) + color[i] as f32 * coverage;
+                    pixel[i] = blended.round() as u8;
+                }
+            });
+        }
+    }
+}
+
+/// Stacks the pod titles and subpod images of `result` into a single image,
+/// similar to what the simple api returns
+///
+/// # Arguments
+///
+/// * `result` - The full results response the images belong to
+/// * `images` - The subpod images, one vector per pod, as returned by
+///   [`Client::fetch_subpod_images`](crate::Client::fetch_subpod_images)
+/// * `options` - Layout options. The pod titles are only drawn if they
+///   include a font.
+pub fn compose_result(
+    result: &QueryResult,
+    images: &[Vec<DynamicImage>],
+    options: &ComposeOptions,
+) -> DynamicImage {
+    let spacing = options.spacing;
+    let inner_width = options.width.saturating_sub(2 * spacing).max(1);
+    let title_height = options.title_size.ceil() as u32;
+
+    let mut items = vec![];
+    for (pod, pod_images) in result.pods.iter().zip(images.iter()) {
+        if options.font.is_some() && !pod.title.is_empty() {
+            items.push(Item::Title(&pod.title));
+        }
+        for img in pod_images {
+            if img.width() > inner_width {
+                let height = (img.height() as u64 * inner_width as u64 / img.width() as u64) as u32;
+                items.push(Item::Image(img.resize_exact(
+                    inner_width,
+                    height.max(1),
+                    imageops::FilterType::Triangle,
+                )));
+            } else {
+                items.push(Item::Image(img.clone()));
+            }
+        }
+    }
+
+    let height = spacing
+        + items
+            .iter()
+            .map(|i| match i {
+                Item::Title(_) => title_height,
+                Item::Image(img) => img.height(),
+            } + spacing)
+            .sum::<u32>();
+
+    let mut canvas = RgbaImage::from_pixel(options.width, height, options.background);
+
+    let mut y = spacing;
+    for item in items {
+        match item {
+            Item::Title(title) => {
+                if let Some(font) = &options.font {
+                    draw_text(
+                        &mut canvas,
+                        font,
+                        options.title_size,
+                        options.foreground,
+                        spacing,
+                        y,
+                        title,
+                    );
+                }
+                y += title_height;
+            }
+            Item::Image(img) => {
+                imageops::overlay(&mut canvas, &img.to_rgba8(), spacing as i64, y as i64);
+                y += img.height();
+            }
+        }
+        y += spacing;
+    }
+
+    DynamicImage::ImageRgba8(canvas)
+}
+
+#[test]
+fn compose_result_layout() {
+    let result: QueryResult = serde_json::from_str::<crate::response::raw_response::RawApiResponse>(
+        r#"{"queryresult":{"success":true,"error":false,"numpods":1,"datatypes":"","timedout":"",
+        "timedoutpods":"","timing":0.1,"parsetiming":0.1,"parsetimedout":false,"recalculate":"",
+        "id":"","host":"","server":"","related":"","version":"2.6","inputstring":"pi",
+        "pods":[{"title":"Decimal approximation","scanner":"Numeric","id":"DecimalApproximation",
+        "position":100,"error":false,"numsubpods":2,"expressiontypes":{"name":"Default"},
+        "subpods":[{"title":"","plaintext":"3.14","img":{"src":"","alt":"","title":"","width":5,"height":5}},
+        {"title":"","plaintext":"3.14","img":{"src":"","alt":"","title":"","width":5,"height":5}}]}]}}"#,
+    )
+    .unwrap()
+    .into();
+
+    let red = DynamicImage::ImageRgba8(RgbaImage::from_pixel(20, 10, Rgba([255, 0, 0, 255])));
+    let wide = DynamicImage::ImageRgba8(RgbaImage::from_pixel(200, 100, Rgba([255, 0, 0, 255])));
+
+    let options = ComposeOptions {
+        width: 100,
+        spacing: 5,
+        ..Default::default()
+    };
+    let composed = compose_result(&result, &[vec![red, wide]], &options);
+
+    // 5 + 10 + 5 + 45 + 5, the wide image is scaled to the 90 pixel inner width
+    assert_eq!(composed.width(), 100);
+    assert_eq!(composed.height(), 70);
+
+    let composed = composed.to_rgba8();
+    assert_eq!(composed.get_pixel(5, 5), &Rgba([255, 0, 0, 255]));
+    assert_eq!(composed.get_pixel(30, 5), &options.background);
+    assert_eq!(composed.get_pixel(94, 60), &Rgba([255, 0, 0, 255]));
+}
+
+#[test]
+fn compose_result_titles() {
+    let result =
+        crate::testing::parse_query_result(crate::testing::fixtures::POPULATION_FRANCE).unwrap();
+    let images: Vec<Vec<DynamicImage>> = result
+        .pods
+        .iter()
+        .map(|p| vec![DynamicImage::new_rgba8(10, 10); p.subpods.len()])
+        .collect();
+
+    // a font whose only glyph, used for every character, is a filled box
+    let font = FontArc::try_from_slice(include_bytes!("testing/fixtures/square.ttf")).unwrap();
+    let options = ComposeOptions {
+        width: 100,
+        spacing: 5,
+        ..ComposeOptions::new(font)
+    };
+
+    let without_titles = compose_result(
+        &result,
+        &images,
+        &ComposeOptions {
+            font: None,
+            ..options.clone()
+        },
+    );
+    let with_titles = compose_result(&result, &images, &options);
+    assert_eq!(
+        with_titles.height(),
+        without_titles.height() + result.pods.len() as u32 * (16 + 5)
+    );
+
+    // the first character of the first title
+    let with_titles = with_titles.to_rgba8();
+    assert_eq!(with_titles.get_pixel(10, 12), &options.foreground);
+    assert_eq!(with_titles.get_pixel(10, 30), &options.background);
+}
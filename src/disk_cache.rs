@@ -0,0 +1,173 @@
+use crate::cache::{CacheBackend, CachedResponse};
+use crate::encoding::stable_hash;
+use crate::transport::HttpResponse;
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    status: u16,
+    /// Seconds since the unix epoch
+    expires: u64,
+}
+
+/// A cache that persists entries as files in a directory, so they survive
+/// restarts. Expired entries are deleted when they are looked up, use
+/// [`DiskCache::prune`] to delete the ones that aren't. Uses `tokio::fs` and
+/// therefore needs a tokio runtime. Requires the `disk-cache` feature,
+/// which is enabled by default.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    /// Stores entries in `dir`, which is created when the first entry is
+    /// written
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        DiskCache { dir: dir.into() }
+    }
+
+    fn paths(&self, key: &str) -> (PathBuf, PathBuf) {
+        let stem = stable_hash(key);
+        (
+            self.dir.join(format!("{}.json", stem)),
+            self.dir.join(format!("{}.body", stem)),
+        )
+    }
+
+    /// Deletes the files of an entry, ignoring errors as caching is best
+    /// effort
+    async fn remove(meta_path: &Path, body_path: &Path) {
+        let _ = tokio::fs::remove_file(meta_path).await;
+        let _ = tokio::fs::remove_file(body_path).await;
+    }
+
+    /// Deletes all expired entries from the directory
+    pub async fn prune(&self) -> io::Result<()> {
+        let mut dir = match tokio::fs::read_dir(&self.dir).await {
+            Ok(d) => d,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        let now = SystemTime::now();
+        while let Some(file) = dir.next_entry().await? {
+            let meta_path = file.path();
+            if meta_path.extension().map_or(true, |e| e != "json") {
+                continue;
+            }
+            let meta = match tokio::fs::read(&meta_path).await {
+                Ok(m) => serde_json::from_slice::<DiskEntry>(&m).ok(),
+                Err(_) => None,
+            };
+            if let Some(meta) = meta {
+                if UNIX_EPOCH + Duration::from_secs(meta.expires) <= now {
+                    Self::remove(&meta_path, &meta_path.with_extension("body")).await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl CacheBackend for DiskCache {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Option<HttpResponse>> {
+        Box::pin(async move {
+            let (meta_path, body_path) = self.paths(key);
+
+            let meta: DiskEntry =
+                serde_json::from_slice(&tokio::fs::read(&meta_path).await.ok()?).ok()?;
+            if meta.key != key {
+                return None;
+            }
+            if UNIX_EPOCH + Duration::from_secs(meta.expires) <= SystemTime::now() {
+                Self::remove(&meta_path, &body_path).await;
+                return None;
+            }
+
+            Some(HttpResponse {
+                status: meta.status,
+                body: Bytes::from(tokio::fs::read(&body_path).await.ok()?),
+            })
+        })
+    }
+
+    fn put(&self, key: String, value: CachedResponse) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            let (meta_path, body_path) = self.paths(&key);
+            let meta = DiskEntry {
+                key,
+                status: value.response.status,
+                expires: value
+                    .expires
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs()),
+            };
+
+            let _ = tokio::fs::create_dir_all(&self.dir).await;
+            // the body is written first so a readable entry always has one
+            if tokio::fs::write(&body_path, &value.response.body)
+                .await
+                .is_ok()
+            {
+                if let Ok(meta) = serde_json::to_vec(&meta) {
+                    let _ = tokio::fs::write(&meta_path, meta).await;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn disk_cache_persists() {
+    let dir = std::env::temp_dir().join(format!("wolframalpha_api_cache_{}", std::process::id()));
+    let response = HttpResponse {
+        status: 200,
+        body: Bytes::from_static(b"65.3 million people"),
+    };
+
+    DiskCache::new(&dir)
+        .put(
+            "v1/result?i=population+france".into(),
+            CachedResponse {
+                response: response.clone(),
+                expires: SystemTime::now() + Duration::from_secs(60),
+            },
+        )
+        .await;
+
+    let reopened = DiskCache::new(&dir);
+    assert_eq!(
+        reopened.get("v1/result?i=population+france").await,
+        Some(response.clone())
+    );
+    assert_eq!(reopened.get("v1/result?i=population+sweden").await, None);
+
+    let expired = |key: &str| {
+        let expired = CachedResponse {
+            response: response.clone(),
+            expires: SystemTime::now() - Duration::from_secs(1),
+        };
+        reopened.put(key.to_string(), expired)
+    };
+    let count = || std::fs::read_dir(&dir).unwrap().count();
+
+    // expired entries are deleted when they are looked up or pruned
+    expired("v1/result?i=population+sweden").await;
+    assert_eq!(count(), 4);
+    assert_eq!(reopened.get("v1/result?i=population+sweden").await, None);
+    assert_eq!(count(), 2);
+    expired("v1/result?i=population+spain").await;
+    reopened.prune().await.unwrap();
+    assert_eq!(count(), 2);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
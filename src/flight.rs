@@ -0,0 +1,151 @@
+use super::prelude::*;
+use super::WolframalphaError;
+use crate::transport::{HttpResponse, StatusError};
+use futures::channel::oneshot;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+type Outcome = Result<Result<HttpResponse, WolframalphaError>, Box<dyn Error + Send + Sync>>;
+type SharedOutcome = Result<Result<HttpResponse, WolframalphaError>, SharedError>;
+
+/// A copy of the error of a request for the callers that waited for it, as
+/// errors can't be cloned
+#[derive(Debug, Clone)]
+enum SharedError {
+    Status(StatusError),
+    Other(String),
+}
+
+impl SharedError {
+    fn new(error: &(dyn Error + Send + Sync + 'static)) -> Self {
+        match error.downcast_ref::<StatusError>() {
+            Some(e) => SharedError::Status(*e),
+            None => SharedError::Other(error.to_string()),
+        }
+    }
+
+    fn into_box(self) -> Box<dyn Error + Send + Sync> {
+        match self {
+            SharedError::Status(e) => Box::new(e),
+            SharedError::Other(message) => message.into(),
+        }
+    }
+}
+
+type Waiters = HashMap<String, Vec<oneshot::Sender<SharedOutcome>>>;
+
+/// Coalesces concurrent identical requests, so only the first one is made
+/// and the others wait for its response. Clones share their requests.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SingleFlight {
+    in_flight: Arc<Mutex<Waiters>>,
+}
+
+/// Removes the request from the in flight ones if the caller making it
+/// stops before it finished, which wakes the waiting callers so one of them
+/// can make it instead
+struct Leader<'a> {
+    in_flight: &'a Mutex<Waiters>,
+    key: &'a str,
+    finished: bool,
+}
+
+impl Leader<'_> {
+    fn finish(mut self) -> Vec<oneshot::Sender<SharedOutcome>> {
+        self.finished = true;
+        self.in_flight
+            .lock()
+            .unwrap()
+            .remove(self.key)
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.in_flight.lock().unwrap().remove(self.key);
+        }
+    }
+}
+
+impl SingleFlight {
+    /// Makes the request identified by `key` with `request`, unless it is
+    /// already in flight, in which case the response of that one is used.
+    /// The caller making the request gets its error as is, the callers
+    /// waiting for it get a [`StatusError`] for error statuses and the
+    /// message of the error otherwise.
+    pub(crate) async fn run<F, Fut>(&self, key: &str, request: F) -> Outcome
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Outcome>,
+    {
+        loop {
+            let receiver = {
+                let mut in_flight = self.in_flight.lock().unwrap();
+                match in_flight.get_mut(key) {
+                    Some(waiters) => {
+                        let (sender, receiver) = oneshot::channel();
+                        waiters.push(sender);
+                        receiver
+                    }
+                    None => {
+                        in_flight.insert(key.to_string(), vec![]);
+                        break;
+                    }
+                }
+            };
+
+            match receiver.await {
+                Ok(Ok(response)) => return Ok(response),
+                Ok(Err(e)) => return Err(e.into_box()),
+                // the caller making the request gave up, try again
+                Err(oneshot::Canceled) => continue,
+            }
+        }
+
+        let leader = Leader {
+            in_flight: &self.in_flight,
+            key,
+            finished: false,
+        };
+        let outcome = request().await;
+
+        let waiters = leader.finish();
+        if !waiters.is_empty() {
+            let shared = match &outcome {
+                Ok(response) => Ok(response.clone()),
+                Err(e) => Err(SharedError::new(&**e)),
+            };
+            for waiter in waiters {
+                let _ = waiter.send(shared.clone());
+            }
+        }
+
+        outcome
+    }
+}
+
+#[test]
+fn single_flight_errors() {
+    use futures::executor::block_on;
+    use std::io;
+
+    let flights = SingleFlight::default();
+    let (sender, receiver) = oneshot::channel::<()>();
+
+    // the first caller makes the request and the second waits for it, the
+    // request finishes once both are waiting
+    let (leader, waiter, _) = block_on(futures::future::join3(
+        flights.run("key", || async {
+            let _ = receiver.await;
+            Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset").into())
+        }),
+        flights.run("key", || async { unreachable!() }),
+        async { sender.send(()).unwrap() },
+    ));
+
+    assert!(leader.unwrap_err().downcast_ref::<io::Error>().is_some());
+    assert_eq!(waiter.unwrap_err().to_string(), "reset");
+}
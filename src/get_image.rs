@@ -1,33 +1,58 @@
 use super::prelude::*;
 use super::*;
+use crate::response::{Image, QueryResult};
+use crate::transport::HttpTransport;
 use image::DynamicImage;
 
+pub(crate) fn decode(bytes: &[u8]) -> Result<DynamicImage, Box<dyn Error + Send + Sync>> {
+    Ok(image::load_from_memory(bytes)?)
+}
+
 /// Performs a simple api request to wolframalpha, returning you the image
 ///
 /// # Arguments
 ///
 /// * `app_id` - The AppID of your wolframalpha application
 /// * `question` - The plaintext question you want to ask wolframalpha
+#[cfg(feature = "reqwest")]
 pub async fn api_retrieve_image(
     app_id: &str,
     question: &str,
 ) -> Result<Result<DynamicImage, WolframalphaError>, Box<dyn Error + Send + Sync>> {
-    if question.trim() == "" {
-        return Ok(Err(WolframalphaError::InvalidQuestion));
-    }
-    let encoded_query = encoding::encode_question(question)?;
-
-    let response = reqwest::get(format!(
-        "http://api.wolframalpha.com/v1/simple?appid={}&i={}",
-        app_id, encoded_query
-    ))
-    .await?;
+    Client::new(app_id).retrieve_image(question).await
+}
 
-    if response.status() == reqwest::StatusCode::NOT_IMPLEMENTED {
-        return Ok(Err(WolframalphaError::InvalidQuestion));
+impl<T: HttpTransport> Client<T> {
+    /// Performs a simple api request, returning you the image
+    pub async fn retrieve_image(
+        &self,
+        question: &str,
+    ) -> Result<Result<DynamicImage, WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        Ok(match self.retrieve_bytes(question).await? {
+            Ok(bytes) => Ok(decode(&bytes)?),
+            Err(e) => Err(e),
+        })
     }
 
-    let img = image::load_from_memory(&response.bytes().await?)?;
+    /// Downloads and decodes the image a response points to
+    pub async fn fetch_image(
+        &self,
+        image: &Image,
+    ) -> Result<DynamicImage, Box<dyn Error + Send + Sync>> {
+        decode(&self.fetch_image_bytes(image).await?)
+    }
 
-    Ok(Ok(img))
+    /// Downloads and decodes the image of every subpod in `result`, see
+    /// [`Client::fetch_subpod_image_bytes`]
+    pub async fn fetch_subpod_images(
+        &self,
+        result: &QueryResult,
+        parallelism: usize,
+    ) -> Result<Vec<Vec<DynamicImage>>, Box<dyn Error + Send + Sync>> {
+        self.fetch_subpod_image_bytes(result, parallelism)
+            .await?
+            .iter()
+            .map(|pod| pod.iter().map(|b| decode(b)).collect())
+            .collect()
+    }
 }
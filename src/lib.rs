@@ -1,7 +1,8 @@
-//! Simplistic API accessor for wolframalpha. Currently only supports questions and image answer
+//! Simplistic API accessor for wolframalpha. Supports the simple (image), short answers,
+//! spoken results and full results apis
 mod prelude {
     pub use std::error::Error;
-    pub use std::fmt::{self, Write};
+    pub use std::fmt::Write;
 }
 use bytes::Bytes;
 use std::error::Error;
@@ -9,82 +10,242 @@ use std::fmt;
 
 pub mod response;
 
+mod app_id;
+pub use app_id::AppId;
+
+mod client;
+pub use client::{Api, Client, Format, LatLong, Location, RequestOptions, UnitSystem};
+
+mod cache;
+pub use cache::{Cache, CacheBackend, CachedResponse, MemoryCache};
+
+#[cfg(feature = "disk-cache")]
+mod disk_cache;
+#[cfg(feature = "disk-cache")]
+pub use disk_cache::DiskCache;
+
+pub use encoding::MAX_QUESTION_LENGTH;
+
+mod flight;
+
+mod sign;
+pub use sign::sign;
+
+mod limit;
+pub use limit::{Quota, QuotaUsage, RateLimiter};
+
+mod retry;
+pub use retry::{default_retryable_error, default_retryable_status, RetryPolicy};
+
+mod transport;
+pub use transport::{HttpResponse, HttpTransport, StatusError};
+#[cfg(feature = "reqwest")]
+pub use transport::ReqwestTransport;
+
+pub mod testing;
+
+pub mod render;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
 #[cfg(feature = "image")]
 mod get_image;
 
 #[cfg(feature = "image")]
 pub use get_image::api_retrieve_image;
 
+#[cfg(feature = "image")]
+mod compose;
+
+#[cfg(feature = "image")]
+pub use compose::{compose_result, ComposeOptions, FontArc};
+
 mod encoding {
-    use super::prelude::*;
-    fn encode_char(c: char) -> bool {
-        if c.is_ascii() {
-            if c.is_alphanumeric() {
-                false
-            } else if "-_.~".contains(c) {
-                false
-            } else {
-                true
-            }
+    use super::WolframalphaError;
+
+    /// Longest question, in characters, that is sent to the apis. Longer
+    /// ones are refused with [`WolframalphaError::QuestionTooLong`] instead.
+    pub const MAX_QUESTION_LENGTH: usize = 2000;
+
+    /// Refuses questions longer than [`MAX_QUESTION_LENGTH`]
+    pub fn validate_question(question: &str) -> Result<(), WolframalphaError> {
+        if question.chars().count() > MAX_QUESTION_LENGTH {
+            Err(WolframalphaError::QuestionTooLong)
         } else {
-            true
+            Ok(())
         }
     }
 
-    pub fn encode_question(s: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
-        let mut res = String::with_capacity(s.len());
+    /// Percent-encodes everything except the unreserved characters of
+    /// RFC 3986, so the result can be used as a query string key or value
+    pub fn encode_component(s: &str) -> String {
+        const HEX: &[u8; 16] = b"0123456789ABCDEF";
 
-        for c in s.chars() {
-            if !encode_char(c) {
-                res.push(c);
-            } else if c == ' ' {
-                res.push('+');
+        let mut res = String::with_capacity(s.len());
+        for b in s.bytes() {
+            if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+                res.push(b as char);
             } else {
-                let mut buf = [0; 4];
-                let n = c.encode_utf8(&mut buf).len();
+                res.push('%');
+                res.push(HEX[(b >> 4) as usize] as char);
+                res.push(HEX[(b & 0xf) as usize] as char);
+            }
+        }
+
+        res
+    }
+
+    /// Builds a query string, encoding every key and value
+    #[derive(Debug, Clone, Default)]
+    pub struct QueryBuilder {
+        params: Vec<(String, String)>,
+    }
 
-                let mut tmp = String::with_capacity(3 * n);
+    impl QueryBuilder {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn param(&mut self, key: &str, value: &str) -> &mut Self {
+            self.params.push((key.to_string(), value.to_string()));
+            self
+        }
+
+        /// The parameters added so far, unencoded
+        pub fn params(&self) -> &[(String, String)] {
+            &self.params
+        }
+
+        pub fn finish(&self) -> String {
+            let params: Vec<String> = self
+                .params
+                .iter()
+                .map(|(k, v)| format!("{}={}", encode_component(k), encode_component(v)))
+                .collect();
+            params.join("&")
+        }
+    }
 
-                for i in 0..n {
-                    write!(tmp, "%{:02x}", buf[i])?;
-                }
+    /// Reverses the encoding of a query string component
+    pub fn decode_component(s: &str) -> String {
+        let bytes = s.as_bytes();
+        let mut res = Vec::with_capacity(bytes.len());
 
-                res.push_str(&tmp);
+        let hex = |b: u8| (b as char).to_digit(16);
+
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'+' => res.push(b' '),
+                b'%' if i + 2 < bytes.len() => match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                    (Some(h), Some(l)) => {
+                        res.push((h * 16 + l) as u8);
+                        i += 2;
+                    }
+                    _ => res.push(b'%'),
+                },
+                b => res.push(b),
             }
+            i += 1;
         }
 
-        Ok(res)
+        String::from_utf8_lossy(&res).into_owned()
+    }
+
+    /// Parameters whose values are secret and must not be written to disk
+    const REDACTED_PARAMS: &[&str] = &["appid", "sig"];
+
+    /// Replaces the values of secret parameters in `url` with `REDACTED`
+    pub fn redact_url(url: &str) -> String {
+        let (base, query) = match url.split_once('?') {
+            Some(x) => x,
+            None => return url.to_string(),
+        };
+
+        let params: Vec<String> = query
+            .split('&')
+            .map(|p| match p.split_once('=') {
+                Some((k, _)) if REDACTED_PARAMS.contains(&k) => format!("{}=REDACTED", k),
+                _ => p.to_string(),
+            })
+            .collect();
+
+        format!("{}?{}", base, params.join("&"))
+    }
+
+    /// FNV-1a as hex, used for file names because unlike the std hasher it
+    /// is stable across rust versions
+    pub fn stable_hash(s: &str) -> String {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for b in s.bytes() {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        format!("{:016x}", hash)
+    }
+
+    #[test]
+    fn query_builder_encoding() {
+        let mut query = QueryBuilder::new();
+        query
+            .param("appid", "AB&C=D")
+            .param("i", "1+1 = 2 #hash")
+            .param("q", "Zürich 東京 😀")
+            .param("safe", "a-b_c.d~e");
+
+        let encoded = query.finish();
+        assert_eq!(
+            encoded,
+            "appid=AB%26C%3DD&i=1%2B1%20%3D%202%20%23hash\
+             &q=Z%C3%BCrich%20%E6%9D%B1%E4%BA%AC%20%F0%9F%98%80&safe=a-b_c.d~e"
+        );
+
+        let decoded: Vec<_> = encoded
+            .split('&')
+            .map(|p| {
+                let (k, v) = p.split_once('=').unwrap();
+                (decode_component(k), decode_component(v))
+            })
+            .collect();
+        assert_eq!(decoded[0].1, "AB&C=D");
+        assert_eq!(decoded[1].1, "1+1 = 2 #hash");
+        assert_eq!(decoded[2].1, "Zürich 東京 😀");
+    }
+
+    #[test]
+    fn question_length_validation() {
+        assert!(validate_question(&"é".repeat(MAX_QUESTION_LENGTH)).is_ok());
+        assert!(matches!(
+            validate_question(&"é".repeat(MAX_QUESTION_LENGTH + 1)),
+            Err(WolframalphaError::QuestionTooLong)
+        ));
     }
 }
 
 /// Does the same thing as `api_retrieve_image` but instead of retrieving
 /// the image it just gives you the raw bytes of the image instead
+#[cfg(feature = "reqwest")]
 pub async fn api_retrieve_bytes(
     app_id: &str,
     question: &str,
 ) -> Result<Result<Bytes, WolframalphaError>, Box<dyn Error + Send + Sync>> {
-    if question.trim() == "" {
-        return Ok(Err(WolframalphaError::InvalidQuestion));
-    }
-
-    let encoded_query = encoding::encode_question(question)?;
-
-    let response = reqwest::get(format!(
-        "http://api.wolframalpha.com/v1/simple?appid={}&i={}",
-        app_id, encoded_query
-    ))
-    .await?;
-
-    if response.status() == reqwest::StatusCode::NOT_IMPLEMENTED {
-        return Ok(Err(WolframalphaError::InvalidQuestion));
-    }
-
-    Ok(Ok(response.bytes().await?))
+    Client::new(app_id).retrieve_bytes(question).await
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum WolframalphaError {
     InvalidQuestion,
+    /// The question is longer than the apis accept, see
+    /// [`MAX_QUESTION_LENGTH`]
+    QuestionTooLong,
+    /// The AppID is malformed, see [`AppId::parse`]
+    InvalidAppId,
+    /// The monthly budget of a [`Quota`] is used up, so the call was not
+    /// made
+    QuotaExceeded,
+    /// A [`LatLong`] location is out of range, see [`LatLong::is_valid`]
+    InvalidLocation,
 }
 
 impl fmt::Display for WolframalphaError {
@@ -94,6 +255,10 @@ impl fmt::Display for WolframalphaError {
             "{}",
             match self {
                 Self::InvalidQuestion => "invalid question",
+                Self::QuestionTooLong => "question too long",
+                Self::InvalidAppId => "invalid AppID",
+                Self::QuotaExceeded => "monthly api call budget exhausted",
+                Self::InvalidLocation => "invalid location",
             }
         )
     }
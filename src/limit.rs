@@ -0,0 +1,323 @@
+use super::prelude::*;
+use super::WolframalphaError;
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+/// A token bucket limiting how fast a [`Client`](crate::Client) makes api
+/// calls. Calls over the limit wait for a token instead of failing. Clones
+/// share their bucket, so one limiter can be used by several clients.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    capacity: f64,
+    /// Tokens added per second
+    rate: f64,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    /// Allows bursts of up to `calls` calls and refills at a rate of `calls`
+    /// per `per`
+    pub fn new(calls: u32, per: Duration) -> Self {
+        let capacity = calls.max(1) as f64;
+        RateLimiter {
+            capacity,
+            rate: capacity / per.as_secs_f64().max(f64::MIN_POSITIVE),
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: capacity,
+                last: Instant::now(),
+            })),
+        }
+    }
+
+    /// Takes a token, returning how long the caller has to wait before
+    /// making its call. Tokens can go negative, so waiting callers are
+    /// served in the order they arrived.
+    pub(crate) fn reserve(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+
+        let now = Instant::now();
+        let refill = now.duration_since(bucket.last).as_secs_f64() * self.rate;
+        bucket.tokens = (bucket.tokens + refill).min(self.capacity) - 1.0;
+        bucket.last = now;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.rate)
+        }
+    }
+}
+
+/// How much of a [`Quota`] has been used in the current month
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaUsage {
+    /// The month the usage is for, as `YYYY-MM` in UTC
+    pub month: String,
+    pub used: u32,
+    pub budget: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QuotaState {
+    month: String,
+    used: u32,
+}
+
+/// The current month as `YYYY-MM` in UTC
+fn current_month() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86400) as i64;
+
+    // civil_from_days from http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}", year, month)
+}
+
+type WarningCallback = Arc<dyn Fn(&QuotaUsage) + Send + Sync>;
+
+/// Counts the api calls made in the current month and refuses calls once
+/// the monthly budget is used up, with
+/// [`WolframalphaError::QuotaExceeded`]. The count starts over every month
+/// and can be persisted to a file so it survives restarts. Clones share
+/// their count.
+#[derive(Clone)]
+pub struct Quota {
+    budget: u32,
+    path: Option<PathBuf>,
+    warn_at: u32,
+    on_warning: Option<WarningCallback>,
+    state: Arc<Mutex<QuotaState>>,
+    /// Held while writing the file
+    file: Arc<Mutex<()>>,
+}
+
+impl fmt::Debug for Quota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Quota")
+            .field("budget", &self.budget)
+            .field("path", &self.path)
+            .field("warn_at", &self.warn_at)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl Quota {
+    /// Allows `budget` calls per month, counted in memory only
+    pub fn new(budget: u32) -> Self {
+        Quota {
+            budget,
+            path: None,
+            warn_at: budget,
+            on_warning: None,
+            state: Arc::new(Mutex::new(QuotaState {
+                month: current_month(),
+                used: 0,
+            })),
+            file: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Allows `budget` calls per month and keeps the count in the file at
+    /// `path`, continuing from the count already in it if there is one
+    pub fn persisted(
+        path: impl Into<PathBuf>,
+        budget: u32,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let path = path.into();
+        let mut quota = Self::new(budget);
+
+        match fs::read(&path) {
+            Ok(contents) => {
+                let state: QuotaState = serde_json::from_slice(&contents)?;
+                if state.month == current_month() {
+                    *quota.state.lock().unwrap() = state;
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        quota.path = Some(path);
+        Ok(quota)
+    }
+
+    /// Calls `callback` before every call made once `calls` calls have been
+    /// made this month, to warn that the budget is running out
+    pub fn with_warning(
+        mut self,
+        calls: u32,
+        callback: impl Fn(&QuotaUsage) + Send + Sync + 'static,
+    ) -> Self {
+        self.warn_at = calls;
+        self.on_warning = Some(Arc::new(callback));
+        self
+    }
+
+    fn usage_of(&self, state: &QuotaState) -> QuotaUsage {
+        QuotaUsage {
+            month: state.month.clone(),
+            used: state.used,
+            budget: self.budget,
+        }
+    }
+
+    /// The calls made this month
+    pub fn usage(&self) -> QuotaUsage {
+        let mut state = self.state.lock().unwrap();
+        let month = current_month();
+        if state.month != month {
+            *state = QuotaState { month, used: 0 };
+        }
+        self.usage_of(&state)
+    }
+
+    /// Counts a call, or refuses it if the budget is used up. If the count
+    /// can't be written to the file the call is not counted.
+    pub(crate) fn acquire(
+        &self,
+    ) -> Result<Result<(), WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        // the callback and the file write happen without holding the lock,
+        // so the callback can look at the quota
+        let before = {
+            let mut state = self.state.lock().unwrap();
+
+            let month = current_month();
+            if state.month != month {
+                *state = QuotaState { month, used: 0 };
+            }
+
+            if state.used >= self.budget {
+                return Ok(Err(WolframalphaError::QuotaExceeded));
+            }
+            let before = self.usage_of(&state);
+            state.used += 1;
+            before
+        };
+
+        if let Some(path) = &self.path {
+            if let Err(e) = self.persist(path) {
+                let mut state = self.state.lock().unwrap();
+                if state.month == before.month {
+                    state.used = state.used.saturating_sub(1);
+                }
+                return Err(e);
+            }
+        }
+
+        if let Some(callback) = &self.on_warning {
+            if before.used >= self.warn_at {
+                callback(&before);
+            }
+        }
+
+        Ok(Ok(()))
+    }
+
+    /// [`Quota::acquire`] for async callers. A persisted quota is counted
+    /// on a thread of its own, so writing the file doesn't block the
+    /// executor.
+    pub(crate) async fn acquire_async(
+        &self,
+    ) -> Result<Result<(), WolframalphaError>, Box<dyn Error + Send + Sync>> {
+        if self.path.is_none() {
+            return self.acquire();
+        }
+
+        let quota = self.clone();
+        let (sender, receiver) = oneshot::channel();
+        thread::spawn(move || {
+            let _ = sender.send(quota.acquire());
+        });
+        receiver.await?
+    }
+
+    /// Writes the current count to `path`. Writes are serialized and always
+    /// write the latest count, so the file ends up with the latest count
+    /// even if concurrent writes finish out of order. The count is written
+    /// to a temporary file that then replaces the file, so a crash while
+    /// writing can't leave a truncated file behind.
+    fn persist(&self, path: &Path) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let _file = self.file.lock().unwrap();
+        let state = self.state.lock().unwrap().clone();
+
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(format!(".{}.tmp", std::process::id()));
+        fs::write(&temp, serde_json::to_vec(&state)?)?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn rate_limiter_reserve() {
+    let limiter = RateLimiter::new(2, Duration::from_secs(10));
+
+    assert_eq!(limiter.reserve(), Duration::ZERO);
+    assert_eq!(limiter.reserve(), Duration::ZERO);
+
+    // the bucket is empty, so the third call waits for one refill and the
+    // fourth for two
+    let third = limiter.reserve();
+    assert!(third > Duration::from_secs(4) && third <= Duration::from_secs(5));
+    let fourth = limiter.reserve();
+    assert!(fourth > Duration::from_secs(9) && fourth <= Duration::from_secs(10));
+}
+
+#[test]
+fn quota_persists_and_refuses() {
+    let path = std::env::temp_dir().join(format!("wolframalpha_api_quota_{}", std::process::id()));
+    let warnings = Arc::new(Mutex::new(vec![]));
+
+    let recorded = warnings.clone();
+    let quota = Quota::persisted(&path, 3).unwrap();
+    let inner = quota.clone();
+    // the callback can look at the quota without deadlocking
+    let quota = quota.with_warning(1, move |u| {
+        assert_eq!(inner.usage().used, u.used + 1);
+        recorded.lock().unwrap().push(u.used);
+    });
+    assert!(quota.acquire().unwrap().is_ok());
+    assert!(quota.acquire().unwrap().is_ok());
+    assert_eq!(*warnings.lock().unwrap(), vec![1]);
+
+    let reloaded = Quota::persisted(&path, 3).unwrap();
+    assert_eq!(reloaded.usage().used, 2);
+    assert!(reloaded.acquire().unwrap().is_ok());
+    assert!(matches!(
+        reloaded.acquire().unwrap(),
+        Err(WolframalphaError::QuotaExceeded)
+    ));
+
+    fs::remove_file(&path).unwrap();
+
+    // a call whose count can't be written is not counted
+    let dir =
+        std::env::temp_dir().join(format!("wolframalpha_api_quota_dir_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let quota = Quota::persisted(dir.join("quota.json"), 3).unwrap();
+    fs::remove_dir(&dir).unwrap();
+    assert!(quota.acquire().is_err());
+    assert_eq!(quota.usage().used, 0);
+}
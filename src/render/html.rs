@@ -0,0 +1,431 @@
+use crate::response::{Image, QueryResult, SubPod, Table};
+
+/// Escapes text for use in html content and quoted attribute values
+fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&#39;"),
+            c => res.push(c),
+        }
+    }
+    res
+}
+
+/// Only http(s) urls are linked, so a response can't smuggle in
+/// `javascript:` urls
+fn safe_url(url: &str) -> Option<String> {
+    let lower = url.trim().to_ascii_lowercase();
+    if lower.starts_with("https://") || lower.starts_with("http://") {
+        Some(escape(url.trim()))
+    } else {
+        None
+    }
+}
+
+/// Presentation MathML elements that are copied to the output. Everything
+/// else is dropped, keeping only its text.
+const MATHML_ELEMENTS: &[&str] = &[
+    "math",
+    "maction",
+    "menclose",
+    "merror",
+    "mfenced",
+    "mfrac",
+    "mi",
+    "mmultiscripts",
+    "mn",
+    "mo",
+    "mover",
+    "mpadded",
+    "mphantom",
+    "mprescripts",
+    "mroot",
+    "mrow",
+    "ms",
+    "mspace",
+    "msqrt",
+    "mstyle",
+    "msub",
+    "msubsup",
+    "msup",
+    "mtable",
+    "mtd",
+    "mtext",
+    "mtr",
+    "munder",
+    "munderover",
+    "none",
+    "semantics",
+];
+
+/// Attributes that are copied to the output, none of which can hold urls
+/// or scripts
+const MATHML_ATTRIBUTES: &[&str] = &[
+    "accent",
+    "accentunder",
+    "close",
+    "columnalign",
+    "columnspacing",
+    "columnspan",
+    "depth",
+    "dir",
+    "display",
+    "displaystyle",
+    "fence",
+    "form",
+    "height",
+    "largeop",
+    "linethickness",
+    "lspace",
+    "mathbackground",
+    "mathcolor",
+    "mathsize",
+    "mathvariant",
+    "maxsize",
+    "minsize",
+    "movablelimits",
+    "notation",
+    "open",
+    "rowalign",
+    "rowspacing",
+    "rowspan",
+    "rspace",
+    "scriptlevel",
+    "separator",
+    "separators",
+    "stretchy",
+    "symmetric",
+    "width",
+    "xmlns",
+];
+
+/// Decodes the entities of xml text, so it can be escaped again
+fn decode_entities(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(i) = rest.find('&') {
+        res.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => match entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                    None => entity
+                        .strip_prefix('#')
+                        .and_then(|d| d.parse().ok())
+                        .and_then(char::from_u32),
+                },
+            };
+            c.map(|c| (c, end + 1))
+        });
+
+        match decoded {
+            Some((c, len)) => {
+                res.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                res.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    res.push_str(rest);
+
+    res
+}
+
+/// Parses the attributes of a tag, e.g. `mathvariant="bold" stretchy='false'`
+fn parse_attributes(mut s: &str) -> Vec<(String, String)> {
+    let mut attributes = vec![];
+
+    loop {
+        s = s.trim_start();
+        let name_end = s
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(s.len());
+        if name_end == 0 {
+            break;
+        }
+        let name = s[..name_end].to_ascii_lowercase();
+        s = s[name_end..].trim_start();
+
+        let value = match s.strip_prefix('=') {
+            Some(v) => {
+                let v = v.trim_start();
+                match v.chars().next() {
+                    Some(q) if q == '"' || q == '\'' => {
+                        let end = v[1..].find(q).map_or(v.len(), |e| e + 1);
+                        s = v.get(end + 1..).unwrap_or("");
+                        v[1..end].to_string()
+                    }
+                    _ => {
+                        let end = v.find(char::is_whitespace).unwrap_or(v.len());
+                        s = &v[end..];
+                        v[..end].to_string()
+                    }
+                }
+            }
+            None => String::new(),
+        };
+
+        attributes.push((name, decode_entities(&value)));
+    }
+
+    attributes
+}
+
+/// Rebuilds `mathml` from allowed elements and attributes only, escaping
+/// all text. Comments, processing instructions and CDATA are dropped. The
+/// elements are balanced, so truncated MathML can't swallow what follows it.
+pub(crate) fn sanitize_mathml(mathml: &str) -> String {
+    let mut res = String::with_capacity(mathml.len());
+    let mut rest = mathml;
+    // allowed elements that are open, innermost last
+    let mut open: Vec<String> = vec![];
+
+    while let Some(start) = rest.find('<') {
+        res.push_str(&escape(&decode_entities(&rest[..start])));
+        rest = &rest[start..];
+
+        let skipped = [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>")]
+            .iter()
+            .find(|(open, _)| rest.starts_with(open))
+            .map(|(_, close)| rest.find(close).map_or("", |e| &rest[e + close.len()..]));
+        if let Some(after) = skipped {
+            rest = after;
+            continue;
+        }
+
+        let end = match rest.find('>') {
+            Some(e) => e,
+            None => {
+                rest = "";
+                break;
+            }
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(t) => (true, t),
+            None => (false, tag),
+        };
+        let (self_closing, tag) = match tag.strip_suffix('/') {
+            Some(t) => (true, t),
+            None => (false, tag),
+        };
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let name = tag[..name_end].to_ascii_lowercase();
+        if !MATHML_ELEMENTS.contains(&name.as_str()) {
+            continue;
+        }
+
+        if closing {
+            // closing an element also closes the ones opened inside it,
+            // closing tags of elements that aren't open are dropped
+            if let Some(i) = open.iter().rposition(|o| *o == name) {
+                for o in open.drain(i..).rev() {
+                    res.push_str(&format!("</{}>", o));
+                }
+            }
+            continue;
+        }
+
+        res.push('<');
+        res.push_str(&name);
+        for (attr, value) in parse_attributes(&tag[name_end..]) {
+            if MATHML_ATTRIBUTES.contains(&attr.as_str()) {
+                res.push_str(&format!(" {}=\"{}\"", attr, escape(&value)));
+            }
+        }
+        if self_closing {
+            res.push('/');
+        } else {
+            open.push(name);
+        }
+        res.push('>');
+    }
+    res.push_str(&escape(&decode_entities(rest)));
+    for o in open.iter().rev() {
+        res.push_str(&format!("</{}>", o));
+    }
+
+    res
+}
+
+fn push_img(out: &mut String, img: &Image) {
+    if let Some(src) = safe_url(&img.source) {
+        out.push_str(&format!(
+            "<img src=\"{}\" alt=\"{}\" width=\"{}\" height=\"{}\">",
+            src,
+            escape(&img.alt),
+            img.width,
+            img.height
+        ));
+    }
+}
+
+fn push_table(out: &mut String, table: &Table) {
+    out.push_str("<table>");
+    if let Some(header) = &table.header {
+        out.push_str("<thead><tr>");
+        for cell in header {
+            out.push_str(&format!("<th>{}</th>", escape(cell)));
+        }
+        out.push_str("</tr></thead>");
+    }
+    out.push_str("<tbody>");
+    for row in &table.rows {
+        out.push_str("<tr>");
+        for cell in row {
+            out.push_str(&format!("<td>{}</td>", escape(cell)));
+        }
+        out.push_str("</tr>");
+    }
+    out.push_str("</tbody></table>");
+}
+
+fn push_subpod(out: &mut String, subpod: &SubPod) {
+    out.push_str("<figure class=\"subpod\">");
+
+    match (&subpod.mathml, &subpod.img, &subpod.plaintext) {
+        (Some(mathml), _, _) => out.push_str(&sanitize_mathml(mathml)),
+        (None, Some(img), _) => push_img(out, img),
+        (None, None, Some(text)) => match Table::parse(text) {
+            Some(table) => push_table(out, &table),
+            None => out.push_str(&format!("<pre>{}</pre>", escape(text))),
+        },
+        (None, None, None) => {}
+    }
+
+    if !subpod.title.is_empty() {
+        out.push_str(&format!(
+            "<figcaption>{}</figcaption>",
+            escape(&subpod.title)
+        ));
+    }
+    out.push_str("</figure>\n");
+}
+
+/// Formats `result` as an html fragment for embedding in a page. Every pod
+/// is a `<section>` with a heading and a `<figure>` per subpod, showing its
+/// MathML if it was requested and its image otherwise, followed by a
+/// `<footer>` crediting the sources.
+///
+/// Everything taken from the response is escaped, MathML is reduced to
+/// known presentation elements and attributes, and only http(s) urls are
+/// linked, so the output is safe to embed even though the response is
+/// not trusted.
+pub fn to_html(result: &QueryResult) -> String {
+    let mut out = String::from("<div class=\"wolframalpha-result\">\n");
+
+    for pod in &result.pods {
+        out.push_str(&format!(
+            "<section class=\"pod\" data-pod-id=\"{}\">\n<h2>{}</h2>\n",
+            escape(&pod.id),
+            escape(&pod.title)
+        ));
+        for subpod in &pod.subpods {
+            push_subpod(&mut out, subpod);
+        }
+        out.push_str("</section>\n");
+    }
+
+    if !result.sources.is_empty() {
+        out.push_str("<footer class=\"sources\">Sources: ");
+        let sources: Vec<String> = result
+            .sources
+            .iter()
+            .map(|s| match safe_url(&s.url) {
+                Some(url) => format!(
+                    "<a href=\"{}\" rel=\"noopener noreferrer\">{}</a>",
+                    url,
+                    escape(&s.text)
+                ),
+                None => escape(&s.text),
+            })
+            .collect();
+        out.push_str(&sources.join(", "));
+        out.push_str("</footer>\n");
+    }
+
+    out.push_str("</div>\n");
+    out
+}
+
+#[test]
+fn html_rendering() {
+    let result =
+        crate::testing::parse_query_result(crate::testing::fixtures::POPULATION_FRANCE).unwrap();
+
+    let html = to_html(&result);
+    assert!(html.starts_with(
+        "<div class=\"wolframalpha-result\">\n<section class=\"pod\" data-pod-id=\"Input\">\n\
+         <h2>Input interpretation</h2>\n<figure class=\"subpod\"><img src=\"https://www6b3.\
+         wolframalpha.com/Calculate/MSP/MSP8895186885ghge360i17000017e20d850ch73bd1?\
+         MSPStoreType=image/gif&amp;s=13\" alt=\"France | population\" width=\"146\" \
+         height=\"23\"></figure>\n</section>\n"
+    ));
+    assert!(html.ends_with(
+        "<footer class=\"sources\">Sources: <a href=\"https://www6b3.wolframalpha.com/sources/\
+         CityDataSourceInformationNotes.html\" rel=\"noopener noreferrer\">City data</a>, \
+         <a href=\"https://www6b3.wolframalpha.com/sources/CountryDataSourceInformationNotes.html\" \
+         rel=\"noopener noreferrer\">Country data</a></footer>\n</div>\n"
+    ));
+}
+
+#[test]
+fn mathml_sanitizing() {
+    assert_eq!(
+        sanitize_mathml(
+            "<math xmlns='http://www.w3.org/1998/Math/MathML' mathvariant=bold>\n\
+             <mrow><mi>x</mi><mo>&lt;</mo><mn>2</mn></mrow><mspace width=\"1em\"/></math>"
+        ),
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" mathvariant=\"bold\">\n\
+         <mrow><mi>x</mi><mo>&lt;</mo><mn>2</mn></mrow><mspace width=\"1em\"/></math>"
+    );
+    assert_eq!(
+        sanitize_mathml(
+            "<math><mi onclick=\"alert(1)\" href=\"javascript:alert(1)\">x</mi>\
+             <script>alert(1)</script><!-- <b> --><mtext>a&amp;b\"</mtext></math>"
+        ),
+        "<math><mi>x</mi>alert(1)<mtext>a&amp;b&quot;</mtext></math>"
+    );
+    assert_eq!(
+        sanitize_mathml("<math><mrow><mi>x</mo></math></mrow>"),
+        "<math><mrow><mi>x</mi></mrow></math>"
+    );
+
+    let mut subpod =
+        crate::testing::parse_query_result(crate::testing::fixtures::POPULATION_FRANCE)
+            .unwrap()
+            .pods[0]
+            .subpods[0]
+            .clone();
+    subpod.mathml = Some("<math><mrow><mi>x".to_string());
+    subpod.title = "cap".to_string();
+    let mut out = String::new();
+    push_subpod(&mut out, &subpod);
+    assert_eq!(
+        out,
+        "<figure class=\"subpod\"><math><mrow><mi>x</mi></mrow></math>\
+         <figcaption>cap</figcaption></figure>\n"
+    );
+}
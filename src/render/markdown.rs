@@ -0,0 +1,131 @@
+use crate::response::{QueryResult, SubPod, Table};
+
+/// Controls the output of [`to_markdown`]
+#[derive(Debug, Clone)]
+pub struct MarkdownOptions {
+    /// Link the subpod images instead of writing out their plaintext. Chat
+    /// services that show linked images inline display these like the
+    /// wolframalpha website.
+    pub images: bool,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        MarkdownOptions { images: true }
+    }
+}
+
+/// Escapes the characters markdown gives a meaning to, and the ones that
+/// start a heading or list at the beginning of a line
+fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for (i, c) in text.chars().enumerate() {
+        if "\\`*_{}[]()<>|~".contains(c) || (i == 0 && "#+-".contains(c)) {
+            res.push('\\');
+        }
+        res.push(c);
+    }
+    res
+}
+
+/// Encodes the characters that would end a markdown link destination
+fn escape_url(url: &str) -> String {
+    url.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+fn push_table(out: &mut String, table: &Table) {
+    let columns = table.rows.first().map_or(0, Vec::len);
+    let header = match &table.header {
+        Some(h) => h.clone(),
+        None => vec![String::new(); columns],
+    };
+
+    let row = |cells: &[String]| {
+        let cells: Vec<String> = cells.iter().map(|c| escape(c)).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+
+    out.push_str(&row(&header));
+    out.push_str(&format!("|{}\n", " --- |".repeat(columns)));
+    for r in &table.rows {
+        out.push_str(&row(r));
+    }
+}
+
+fn push_subpod(out: &mut String, options: &MarkdownOptions, subpod: &SubPod) {
+    if !subpod.title.is_empty() {
+        out.push_str(&format!("**{}**\n\n", escape(&subpod.title)));
+    }
+
+    match (&subpod.img, &subpod.plaintext) {
+        (Some(img), _) if options.images => {
+            out.push_str(&format!(
+                "![{}]({})\n\n",
+                escape(&img.alt),
+                escape_url(&img.source)
+            ));
+        }
+        (_, Some(text)) if !text.trim().is_empty() => {
+            match Table::parse(text) {
+                Some(table) => push_table(out, &table),
+                None => {
+                    let lines: Vec<String> = text.lines().map(|l| escape(l.trim())).collect();
+                    // two trailing spaces keep the line breaks
+                    out.push_str(&lines.join("  \n"));
+                    out.push('\n');
+                }
+            }
+            out.push('\n');
+        }
+        _ => {}
+    }
+}
+
+/// Formats `result` as Markdown, for chat services and the like: every pod
+/// as a heading followed by its subpods, and the sources as links at the end
+pub fn to_markdown(result: &QueryResult, options: &MarkdownOptions) -> String {
+    let mut out = String::new();
+
+    for pod in &result.pods {
+        out.push_str(&format!("## {}\n\n", escape(&pod.title)));
+        for subpod in &pod.subpods {
+            push_subpod(&mut out, options, subpod);
+        }
+    }
+
+    if !result.sources.is_empty() {
+        let sources: Vec<String> = result
+            .sources
+            .iter()
+            .map(|s| format!("[{}]({})", escape(&s.text), escape_url(&s.url)))
+            .collect();
+        out.push_str(&format!("Sources: {}\n", sources.join(", ")));
+    }
+
+    out
+}
+
+#[test]
+fn markdown_rendering() {
+    let result =
+        crate::testing::parse_query_result(crate::testing::fixtures::POPULATION_FRANCE).unwrap();
+
+    let text = to_markdown(&result, &MarkdownOptions::default());
+    assert!(text.starts_with(
+        "## Input interpretation\n\n![France \\| population](https://www6b3.wolframalpha.com/\
+         Calculate/MSP/MSP8895186885ghge360i17000017e20d850ch73bd1?MSPStoreType=image/gif&s=13)\n\n"
+    ));
+    assert!(text.ends_with(
+        "Sources: [City data](https://www6b3.wolframalpha.com/sources/\
+         CityDataSourceInformationNotes.html), [Country data](https://www6b3.wolframalpha.com/\
+         sources/CountryDataSourceInformationNotes.html)\n"
+    ));
+
+    let text = to_markdown(&result, &MarkdownOptions { images: false });
+    assert!(text.starts_with(
+        "## Input interpretation\n\n|  |  |\n| --- | --- |\n| France | population |\n\n## Result\n\n\
+         65.3 million people \\(world rank: 22nd\\) \\(2020 estimate\\)\n\n"
+    ));
+}
@@ -0,0 +1,58 @@
+//! Text renderings of a cooked [`QueryResult`](crate::response::QueryResult):
+//! for terminals, for chat services as Markdown, and as html to embed in a
+//! page
+mod html;
+mod markdown;
+mod terminal;
+pub use html::to_html;
+pub use markdown::{to_markdown, MarkdownOptions};
+pub use terminal::{to_terminal, TerminalOptions};
+
+/// Splits `text` into lines of at most `width` characters, breaking at
+/// whitespace where possible. Existing line breaks are kept.
+pub(crate) fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = vec![];
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut len = 0;
+
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+
+            if len > 0 && len + 1 + word.len() > width {
+                lines.push(std::mem::take(&mut line));
+                len = 0;
+            }
+            // words longer than a line are broken up
+            while word.len() > width - len {
+                let rest = word.split_off(width - len);
+                line.extend(word);
+                lines.push(std::mem::take(&mut line));
+                len = 0;
+                word = rest;
+            }
+            if len > 0 {
+                line.push(' ');
+                len += 1;
+            }
+            len += word.len();
+            line.extend(word);
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[test]
+fn wrap_lines() {
+    assert_eq!(
+        wrap("the quick brown fox\njumps", 10),
+        vec!["the quick", "brown fox", "jumps"]
+    );
+    assert_eq!(wrap("abcdefghij klm", 4), vec!["abcd", "efgh", "ij", "klm"]);
+    assert!(wrap("", 10).is_empty());
+}
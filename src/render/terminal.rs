@@ -0,0 +1,193 @@
+use super::wrap;
+use crate::response::{Assumption, QueryResult, SubPod, Table};
+
+/// Controls the output of [`to_terminal`]
+#[derive(Debug, Clone, Default)]
+pub struct TerminalOptions {
+    /// Highlight titles and dim urls with ANSI escape codes
+    pub color: bool,
+    /// Wrap text to this many columns. Tables are not wrapped.
+    pub width: Option<usize>,
+}
+
+const BOLD: &str = "1";
+const HEADER: &str = "1;34";
+const DIM: &str = "2";
+
+impl TerminalOptions {
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Writes `text` indented by `indent` spaces, wrapped to the width
+    fn push_text(&self, out: &mut String, indent: usize, text: &str) {
+        let lines = match self.width {
+            Some(w) => wrap(text, w.saturating_sub(indent)),
+            None => text.lines().map(|l| l.trim().to_string()).collect(),
+        };
+        for line in lines {
+            out.push_str(&" ".repeat(indent));
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+}
+
+/// Rows with only a first cell, such as `(2017 estimate)` below a table,
+/// are notes rather than data
+fn is_note(row: &[String]) -> bool {
+    row.iter().skip(1).all(String::is_empty)
+}
+
+fn push_table(out: &mut String, options: &TerminalOptions, indent: usize, table: &Table) {
+    let grid = table.to_grid();
+    let columns = grid.first().map_or(0, Vec::len);
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| {
+            grid.iter()
+                .filter(|row| !is_note(row))
+                .map(|row| row[c].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for (i, row) in grid.iter().enumerate() {
+        if is_note(row) {
+            options.push_text(out, indent, &row[0]);
+            continue;
+        }
+
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, w)| format!("{:<1$}", cell, w))
+            .collect();
+        let line = cells.join(" | ");
+        let line = line.trim_end();
+
+        out.push_str(&" ".repeat(indent));
+        if i == 0 && table.header.is_some() {
+            out.push_str(&options.paint(BOLD, line));
+            out.push('\n');
+            out.push_str(&" ".repeat(indent));
+            let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+            out.push_str(&rule.join("-+-"));
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+}
+
+fn push_subpod(out: &mut String, options: &TerminalOptions, subpod: &SubPod) {
+    if !subpod.title.is_empty() {
+        out.push_str("  ");
+        out.push_str(&options.paint(BOLD, &subpod.title));
+        out.push('\n');
+    }
+
+    match (&subpod.plaintext, &subpod.img) {
+        (Some(text), _) if !text.trim().is_empty() => match Table::parse(text) {
+            Some(table) => push_table(out, options, 2, &table),
+            None => options.push_text(out, 2, text),
+        },
+        (_, Some(img)) => {
+            out.push_str("  ");
+            out.push_str(&options.paint(DIM, &format!("[image] {}", img.source)));
+            out.push('\n');
+        }
+        _ => {}
+    }
+}
+
+fn push_assumption(out: &mut String, options: &TerminalOptions, assumption: &Assumption) {
+    let about = match (&assumption.word, &assumption.desc) {
+        (Some(word), _) => format!("\"{}\" ({})", word, assumption.assumption_type),
+        (None, Some(desc)) => format!("{} ({})", desc, assumption.assumption_type),
+        (None, None) => assumption.assumption_type.clone(),
+    };
+    options.push_text(out, 2, &about);
+
+    for value in &assumption.values {
+        options.push_text(out, 4, &format!("- {}", value.desc));
+        out.push_str(&" ".repeat(6));
+        out.push_str(&options.paint(DIM, &format!("input: {}", value.input)));
+        out.push('\n');
+    }
+}
+
+/// Formats `result` as plain text for a terminal: every pod as a header
+/// followed by its subpods, with tabular plaintext aligned into columns,
+/// then the assumptions wolframalpha made and the sources it used as
+/// numbered footnotes
+pub fn to_terminal(result: &QueryResult, options: &TerminalOptions) -> String {
+    let mut out = String::new();
+
+    for pod in &result.pods {
+        out.push_str(&options.paint(HEADER, &pod.title));
+        out.push('\n');
+        for subpod in &pod.subpods {
+            push_subpod(&mut out, options, subpod);
+        }
+        out.push('\n');
+    }
+
+    if !result.assumptions.is_empty() {
+        out.push_str(&options.paint(HEADER, "Assumptions"));
+        out.push('\n');
+        for assumption in &result.assumptions {
+            push_assumption(&mut out, options, assumption);
+        }
+        out.push('\n');
+    }
+
+    if !result.sources.is_empty() {
+        out.push_str(&options.paint(HEADER, "Sources"));
+        out.push('\n');
+        for (i, source) in result.sources.iter().enumerate() {
+            options.push_text(&mut out, 2, &format!("[{}] {}", i + 1, source.text));
+            out.push_str(&" ".repeat(6));
+            out.push_str(&options.paint(DIM, &source.url));
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+#[test]
+fn terminal_rendering() {
+    let result =
+        crate::testing::parse_query_result(crate::testing::fixtures::POPULATION_FRANCE).unwrap();
+
+    let text = to_terminal(&result, &TerminalOptions::default());
+    assert!(text.starts_with("Input interpretation\n  France | population\n\nResult\n"));
+    assert!(text.contains(
+        "Largest cities\n  city                                  | population\n  \
+         Paris, Ile-de-France                  | 2.206 million people\n"
+    ));
+    assert!(text.contains(
+        "Nice, Provence-Alpes-Cote-d'Azur      | 342522 people\n  \
+         (2004, 2007, 2013, 2014, 2015, and 2017 estimates)\n"
+    ));
+    assert!(text.ends_with(
+        "Sources\n  [1] City data\n      \
+         https://www6b3.wolframalpha.com/sources/CityDataSourceInformationNotes.html\n  \
+         [2] Country data\n      \
+         https://www6b3.wolframalpha.com/sources/CountryDataSourceInformationNotes.html\n"
+    ));
+    assert!(!text.contains('\x1b'));
+
+    let options = TerminalOptions {
+        color: true,
+        width: Some(30),
+    };
+    let text = to_terminal(&result, &options);
+    assert!(text.starts_with("\x1b[1;34mInput interpretation\x1b[0m\n"));
+    assert!(text.contains("  65.3 million people (world\n  rank: 22nd) (2020 estimate)\n"));
+}
@@ -1,70 +1,580 @@
 pub mod raw_response;
 use raw_response::{pod::*, *};
 
+mod table;
+pub use table::Table;
+
+fn split_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct QueryResult {
-    success: bool,
+    pub success: bool,
+    pub error: Option<ApiError>,
+    pub data_types: Vec<String>,
+    pub timed_out: Vec<String>,
+    pub timing: f32,
+    pub parse_timing: f32,
+    pub parse_timed_out: bool,
+    pub id: String,
+    pub host: String,
+    pub server: String,
+    pub related: String,
+    pub version: String,
+    pub input_string: String,
+    pub pods: Vec<Pod>,
+    pub sources: Vec<Source>,
+    pub assumptions: Vec<Assumption>,
+    pub warnings: Vec<Warning>,
+    /// Suggested rephrasings of the question, sorted by descending score
+    pub did_you_means: Vec<DidYouMean>,
+    pub tips: Vec<String>,
+    pub language_msg: Option<LanguageMsg>,
+    pub future_topic: Option<FutureTopic>,
+    pub example_page: Option<ExamplePage>,
+    /// Set by [`Client::query`](crate::Client::query) when the question was
+    /// not understood and a "did you mean" suggestion was asked instead
+    pub substitution: Option<Substitution>,
+    /// Set when the api answered a different question than the one asked,
+    /// which only happens when reinterpretation is enabled
+    pub reinterpretation: Option<Reinterpretation>,
+}
+
+/// A question the api reinterpreted as a different one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reinterpretation {
+    /// The question that was asked
+    pub original: String,
+    /// The question that was answered
+    pub new: String,
+    /// Other questions the api considered
+    pub alternatives: Vec<String>,
+}
+
+/// A question that was replaced by a "did you mean" suggestion
+#[derive(Debug, Clone, PartialEq)]
+pub struct Substitution {
+    /// The question that was originally asked
+    pub original: String,
+    pub suggestion: DidYouMean,
+}
+
+/// What kind of answer a full results query produced, see
+/// [`QueryResult::outcome`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QueryOutcome<'a> {
+    /// The query was understood and has pods
+    Success,
+    /// The api reported an error, such as an invalid AppID
+    Error(&'a ApiError),
+    /// The question was not understood, but there are suggestions for what
+    /// was meant
+    DidYouMean(&'a [DidYouMean]),
+    /// The question is about a topic wolframalpha does not support yet
+    FutureTopic(&'a FutureTopic),
+    /// The question was not understood
+    NoResult,
+}
+
+impl QueryResult {
+    /// Returns the highest scoring "did you mean" suggestion if the question
+    /// was not understood and the suggestion scores at least `min_score`
+    pub fn best_did_you_mean(&self, min_score: f32) -> Option<&DidYouMean> {
+        match self.outcome() {
+            QueryOutcome::DidYouMean(d) => d.first().filter(|d| d.score >= min_score),
+            _ => None,
+        }
+    }
+
+    /// Classifies the result
+    pub fn outcome(&self) -> QueryOutcome<'_> {
+        if let Some(e) = &self.error {
+            QueryOutcome::Error(e)
+        } else if self.success {
+            QueryOutcome::Success
+        } else if let Some(f) = &self.future_topic {
+            QueryOutcome::FutureTopic(f)
+        } else if !self.did_you_means.is_empty() {
+            QueryOutcome::DidYouMean(&self.did_you_means)
+        } else {
+            QueryOutcome::NoResult
+        }
+    }
 }
 
+impl From<raw_response::QueryResult> for QueryResult {
+    fn from(x: raw_response::QueryResult) -> Self {
+        let mut res = QueryResult {
+            success: x.success,
+            error: match x.error {
+                ErrorWrapper::Ok(_) => None,
+                ErrorWrapper::Error(e) => Some(e.into()),
+            },
+            data_types: split_list(&x.datatypes),
+            timed_out: split_list(&x.timedout),
+            timing: x.timing,
+            parse_timing: x.parsetiming,
+            parse_timed_out: x.parsetimedout,
+            id: x.id,
+            host: x.host,
+            server: x.server,
+            related: x.related,
+            version: x.version,
+            input_string: x.inputstring,
+            pods: x
+                .pods
+                .map(|p| p.into_iter().map(Into::into).collect())
+                .unwrap_or_default(),
+            sources: x
+                .sources
+                .map(|s| s.into_iter().map(Into::into).collect())
+                .unwrap_or_default(),
+            assumptions: x
+                .assumptions
+                .map(|a| a.into_iter().map(Into::into).collect())
+                .unwrap_or_default(),
+            warnings: x
+                .warnings
+                .map(|w| w.into_iter().flat_map(warnings_from_raw).collect())
+                .unwrap_or_default(),
+            did_you_means: {
+                let mut d: Vec<DidYouMean> = x
+                    .didyoumeans
+                    .map(|d| d.into_iter().map(Into::into).collect())
+                    .unwrap_or_default();
+                d.sort_by(|a, b| {
+                    b.score
+                        .partial_cmp(&a.score)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+                d
+            },
+            tips: x
+                .tips
+                .map(|t| t.into_iter().map(|t| t.text).collect())
+                .unwrap_or_default(),
+            language_msg: x.languagemsg.map(Into::into),
+            future_topic: x.futuretopic.map(Into::into),
+            example_page: x.examplepage.map(Into::into),
+            substitution: None,
+            reinterpretation: None,
+        };
+
+        res.reinterpretation = res.warnings.iter().find_map(|w| match w {
+            Warning::Reinterpret(r) => Some(Reinterpretation {
+                original: res.input_string.clone(),
+                new: r.new.clone(),
+                alternatives: r.alternatives.iter().map(|a| a.val.clone()).collect(),
+            }),
+            _ => None,
+        });
+
+        res
+    }
+}
+
+impl From<RawApiResponse> for QueryResult {
+    fn from(x: RawApiResponse) -> Self {
+        x.queryresult.into()
+    }
+}
+
+/// An error reported by the full results api inside of an otherwise valid response
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+    pub code: String,
+    pub msg: String,
+}
+
+impl From<FullApiResponseError> for ApiError {
+    fn from(x: FullApiResponseError) -> Self {
+        ApiError {
+            code: x.code,
+            msg: x.msg,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Source {
+    pub url: String,
+    pub text: String,
+}
+
+impl From<raw_response::Source> for Source {
+    fn from(x: raw_response::Source) -> Self {
+        Source {
+            url: x.url,
+            text: x.text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// A word of the question was replaced with a correctly spelled one
+    Spellcheck(SpellcheckWarning),
+    /// The question has unmatched brackets or similar
+    Delimiters(DelimitersWarning),
+    /// The question was translated to english
+    Translation(TranslationWarning),
+    /// The question was reinterpreted as a different question
+    Reinterpret(ReinterpretWarning),
+}
+
+fn warnings_from_raw(x: Warnings) -> Vec<Warning> {
+    let mut res = vec![];
+
+    if let Some(s) = x.spellcheck {
+        res.extend(s.into_iter().map(|s| Warning::Spellcheck(s.into())));
+    }
+    if let Some(d) = x.delimiters {
+        res.push(Warning::Delimiters(d.into()));
+    }
+    if let Some(t) = x.translation {
+        res.push(Warning::Translation(t.into()));
+    }
+    if let Some(r) = x.reinterpret {
+        res.push(Warning::Reinterpret(r.into()));
+    }
+
+    res
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpellcheckWarning {
+    pub word: String,
+    pub suggestion: String,
+    pub text: String,
+}
+
+impl From<raw_response::SpellcheckWarning> for SpellcheckWarning {
+    fn from(x: raw_response::SpellcheckWarning) -> Self {
+        SpellcheckWarning {
+            word: x.word,
+            suggestion: x.suggestion,
+            text: x.text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelimitersWarning {
+    pub text: String,
+}
+
+impl From<raw_response::DelimitersWarning> for DelimitersWarning {
+    fn from(x: raw_response::DelimitersWarning) -> Self {
+        DelimitersWarning { text: x.text }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranslationWarning {
+    pub phrase: String,
+    pub translation: String,
+    pub language: String,
+    pub text: String,
+}
+
+impl From<raw_response::TranslationWarning> for TranslationWarning {
+    fn from(x: raw_response::TranslationWarning) -> Self {
+        TranslationWarning {
+            phrase: x.phrase,
+            translation: x.trans,
+            language: x.lang,
+            text: x.text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReinterpretWarning {
+    pub text: String,
+    /// The question that was answered instead
+    pub new: String,
+    pub score: Option<f32>,
+    pub level: Option<String>,
+    pub alternatives: Vec<DidYouMean>,
+}
+
+impl From<raw_response::ReinterpretWarning> for ReinterpretWarning {
+    fn from(x: raw_response::ReinterpretWarning) -> Self {
+        ReinterpretWarning {
+            text: x.text,
+            new: x.new,
+            score: x.score.map(|s| s.get_val()),
+            level: x.level,
+            alternatives: x
+                .alternative
+                .map(|a| a.into_iter().map(Into::into).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DidYouMean {
+    /// How likely the suggestion is to be what was meant, between 0 and 1
+    pub score: f32,
+    pub level: String,
+    pub val: String,
+}
+
+impl From<raw_response::DidYouMean> for DidYouMean {
+    fn from(x: raw_response::DidYouMean) -> Self {
+        DidYouMean {
+            score: x.score.get_val(),
+            level: x.level,
+            val: x.val,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageMsg {
+    pub english: String,
+    /// The message in the language the question was asked in
+    pub other: String,
+}
+
+impl From<raw_response::LanguageMsg> for LanguageMsg {
+    fn from(x: raw_response::LanguageMsg) -> Self {
+        LanguageMsg {
+            english: x.english,
+            other: x.other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FutureTopic {
+    pub topic: String,
+    pub msg: String,
+}
+
+impl From<raw_response::FutureTopic> for FutureTopic {
+    fn from(x: raw_response::FutureTopic) -> Self {
+        FutureTopic {
+            topic: x.topic,
+            msg: x.msg,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExamplePage {
+    pub category: String,
+    pub url: String,
+}
+
+impl From<raw_response::ExamplePage> for ExamplePage {
+    fn from(x: raw_response::ExamplePage) -> Self {
+        ExamplePage {
+            category: x.category,
+            url: x.url,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assumption {
+    pub assumption_type: String,
+    pub word: Option<String>,
+    pub desc: Option<String>,
+    pub current: Option<String>,
+    pub template: Option<String>,
+    pub values: Vec<AssumptionValue>,
+}
+
+impl From<raw_response::Assumption> for Assumption {
+    fn from(x: raw_response::Assumption) -> Self {
+        Assumption {
+            assumption_type: x.r#type,
+            word: x.word,
+            desc: x.desc,
+            current: x.current,
+            template: x.template,
+            values: x.values.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssumptionValue {
+    pub name: String,
+    pub desc: String,
+    pub valid: bool,
+    pub input: String,
+}
+
+impl From<raw_response::AssumptionValue> for AssumptionValue {
+    fn from(x: raw_response::AssumptionValue) -> Self {
+        AssumptionValue {
+            name: x.name,
+            desc: x.desc,
+            valid: match x.valid {
+                Some(BoolOrText::Bool(b)) => b,
+                Some(BoolOrText::Text(t)) => t != "false",
+                None => true,
+            },
+            input: x.input,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Pod {
-    title: String,
-    scanner: String,
-    id: String,
-    position: i32,
-    subpods: Vec<SubPod>,
-    expressiontypes: Vec<String>,
-    states: Vec<State>,
-    infos: Vec<Info>,
-    primary: bool,
-    definitions: Vec<Definition>,
-}
-
-struct SubPod {
-    title: String,
-    primary: bool,
-    image_source: Option<String>,
-    micro_sources: Vec<String>,
-    data_sources: Vec<String>,
-    img: Image,
-    plaintext: String,
-    infos: Vec<Info>,
+    pub title: String,
+    pub scanner: String,
+    pub id: String,
+    pub position: i32,
+    pub subpods: Vec<SubPod>,
+    pub expressiontypes: Vec<String>,
+    pub states: Vec<State>,
+    pub infos: Vec<Info>,
+    pub primary: bool,
+    pub definitions: Vec<Definition>,
+    /// Audio of the pod, present when requested with
+    /// [`Format::Sound`](crate::Format::Sound) or
+    /// [`Format::Wav`](crate::Format::Wav)
+    pub sounds: Vec<Sound>,
+}
+
+impl From<RawPod> for Pod {
+    fn from(x: RawPod) -> Self {
+        Pod {
+            title: x.title,
+            scanner: x.scanner,
+            id: x.id,
+            position: x.position,
+            subpods: x.subpods.into_iter().map(Into::into).collect(),
+            expressiontypes: x.expressiontypes.into_iter().map(|e| e.name).collect(),
+            states: x
+                .states
+                .map(|s| s.into_iter().map(Into::into).collect())
+                .unwrap_or_default(),
+            infos: x.infos.into_iter().map(Into::into).collect(),
+            primary: x.primary.unwrap_or(false),
+            definitions: x
+                .definitions
+                .map(|d| d.into_iter().map(Into::into).collect())
+                .unwrap_or_default(),
+            sounds: x
+                .sounds
+                .map(|s| s.sound.into_iter().map(Into::into).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubPod {
+    pub title: String,
+    pub primary: bool,
+    pub image_source: Option<String>,
+    pub micro_sources: Vec<String>,
+    pub data_sources: Vec<String>,
+    pub img: Option<Image>,
+    pub plaintext: Option<String>,
+    /// The subpod as presentation MathML, present when requested with
+    /// [`Format::MathMl`](crate::Format::MathMl)
+    pub mathml: Option<String>,
+    /// Wolfram Language input that reproduces the subpod, present when
+    /// requested with [`Format::Minput`](crate::Format::Minput)
+    pub minput: Option<String>,
+    /// The subpod as Wolfram Language output, present when requested with
+    /// [`Format::Moutput`](crate::Format::Moutput)
+    pub moutput: Option<String>,
+    /// The notebook cell exactly as the api returned it, present when
+    /// requested with [`Format::Cell`](crate::Format::Cell)
+    pub cell: Option<serde_json::Value>,
+    pub infos: Vec<Info>,
+}
+
+impl SubPod {
+    /// Parses the plaintext representation of this subpod as a pipe
+    /// delimited table. Returns `None` if the plaintext is missing or has no
+    /// cells.
+    pub fn table(&self) -> Option<Table> {
+        self.plaintext.as_deref().and_then(Table::parse)
+    }
+}
+
+impl From<RawSubPod> for SubPod {
+    fn from(x: RawSubPod) -> Self {
+        SubPod {
+            title: x.title,
+            primary: x.primary.unwrap_or(false),
+            image_source: x.imagesource,
+            micro_sources: x
+                .microsources
+                .map(|m| m.microsource.into_iter().collect())
+                .unwrap_or_default(),
+            data_sources: x
+                .datasources
+                .map(|d| d.datasource.into_iter().collect())
+                .unwrap_or_default(),
+            img: x.img.map(Into::into),
+            plaintext: x.plaintext,
+            mathml: x.mathml,
+            minput: x.minput,
+            moutput: x.moutput,
+            cell: x.cell,
+            infos: x.infos.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Audio output of a pod, such as the tone of a musical note
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sound {
+    pub url: String,
+    /// MIME type of the audio, e.g. `audio/midi`
+    pub sound_type: String,
+}
+
+impl From<RawSound> for Sound {
+    fn from(x: RawSound) -> Self {
+        Sound {
+            url: x.url,
+            sound_type: x.r#type,
+        }
+    }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Definition {
-    word: String,
-    description: String,
+    pub word: String,
+    pub description: String,
+}
+
+impl From<RawDefinition> for Definition {
+    fn from(x: RawDefinition) -> Self {
+        Definition {
+            word: x.word,
+            description: x.desc,
+        }
+    }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Info {
-    units: Option<Units>,
-    text: Option<String>,
-    image: Option<Image>,
-    links: Vec<Link>,
+    pub units: Option<Units>,
+    pub text: Option<String>,
+    pub image: Option<Image>,
+    pub links: Vec<Link>,
 }
 
 impl From<RawInfo> for Info {
     fn from(x: RawInfo) -> Self {
         Info {
-            units: {
-                match x.units {
-                    Some(units) => {
-                        let mut source: Option<UnitSource> = None;
-                        let mut units_res: Vec<MeasurementUnit> = vec![];
-                        for i in units.iter() {
-                            match i {
-                                RawUnitsWrapper::MeasurementUnits(u) => {units_res.extend(u.into_iter().map(Into::into));},
-                                RawUnitsWrapper::Src(src) => {source = Some((*src).into());}
-                            };
-                        }
-
-                        Some(Units{
-                            units: units_res,
-                            source: source.unwrap()
-                        })
-                        
-                    },
-                    None => None
-                }
-            },
+            units: x.units.map(Into::into),
             text: x.text,
             image: x.img.map(Into::into),
             links: {
@@ -79,20 +589,36 @@ impl From<RawInfo> for Info {
     }
 }
 
-struct Units {
-    units: Vec<MeasurementUnit>,
-    source: UnitSource,
+/// The units used by the values of a pod
+#[derive(Debug, Clone, PartialEq)]
+pub struct Units {
+    pub units: Vec<MeasurementUnit>,
+    /// An image listing the units with their names
+    pub legend: Option<UnitSource>,
 }
 
-enum Unit {
-    Time(MeasurementUnit),
-    Source(UnitSource),
-}
+impl From<Vec<RawUnitsWrapper>> for Units {
+    fn from(x: Vec<RawUnitsWrapper>) -> Self {
+        let mut units = vec![];
+        let mut legend = None;
 
+        for i in x {
+            match i {
+                RawUnitsWrapper::MeasurementUnits(u) => units.extend(u.into_iter().map(Into::into)),
+                RawUnitsWrapper::Src(src) => legend = Some(src.into()),
+            }
+        }
 
-struct MeasurementUnit {
-    short: String,
-    long: String,
+        Units { units, legend }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasurementUnit {
+    /// The abbreviation used in the pod, e.g. `km`
+    pub short: String,
+    /// The full name of the unit, e.g. `kilometers`
+    pub long: String,
 }
 
 impl From<RawMeasurementUnit> for MeasurementUnit {
@@ -101,32 +627,34 @@ impl From<RawMeasurementUnit> for MeasurementUnit {
     }
 }
 
-struct UnitSource {
-    src: String,
-    width: String,
-    height: String,
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitSource {
+    pub source: String,
+    pub width: i32,
+    pub height: i32,
 }
 
 impl From<RawUnitSource> for UnitSource {
     fn from(x: RawUnitSource) -> Self {
         UnitSource {
-            src: x.src,
-            width: x.width,
-            height: x.height,
+            source: x.src,
+            width: x.width.get_val(),
+            height: x.height.get_val(),
         }
     }
 }
 
-struct Image {
-    source: String,
-    alt: String,
-    title: String,
-    width: i32,
-    height: i32,
-    image_type: Option<String>,
-    themes: Option<String>,
-    color_invertable: bool,
-    content_type: Option<String>,
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub source: String,
+    pub alt: String,
+    pub title: String,
+    pub width: i32,
+    pub height: i32,
+    pub image_type: Option<String>,
+    pub themes: Option<String>,
+    pub color_invertable: bool,
+    pub content_type: Option<String>,
 }
 
 impl From<RawImage> for Image {
@@ -145,10 +673,11 @@ impl From<RawImage> for Image {
     }
 }
 
-struct Link {
-    title: Option<String>,
-    url: String,
-    text: String,
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub title: Option<String>,
+    pub url: String,
+    pub text: String,
 }
 
 impl From<RawLink> for Link {
@@ -161,7 +690,8 @@ impl From<RawLink> for Link {
     }
 }
 
-enum State {
+#[derive(Debug, Clone, PartialEq)]
+pub enum State {
     Single(SingleState),
     Multi(MultiState),
 }
@@ -175,10 +705,11 @@ impl From<RawStateWrapper> for State {
     }
 }
 
-struct SingleState {
-    name: String,
-    input: String,
-    stepbystep: bool,
+#[derive(Debug, Clone, PartialEq)]
+pub struct SingleState {
+    pub name: String,
+    pub input: String,
+    pub stepbystep: bool,
 }
 
 impl From<RawState> for SingleState {
@@ -191,11 +722,12 @@ impl From<RawState> for SingleState {
     }
 }
 
-struct MultiState {
-    count: i32,
-    value: String,
-    delimiters: String,
-    states: Vec<SingleState>,
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiState {
+    pub count: i32,
+    pub value: String,
+    pub delimiters: String,
+    pub states: Vec<SingleState>,
 }
 
 impl From<RawMultiState> for MultiState {
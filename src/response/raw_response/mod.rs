@@ -1,14 +1,33 @@
-use serde::{Deserialize, Serialize};
-use serde_json::Result;
+use serde::Deserialize;
 
 pub mod pod;
 use pod::*;
 
+/// A field the api sends as a bare value when there is one and as an array
+/// when there are several
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> IntoIterator for OneOrMany<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Self::One(r) => vec![r].into_iter(),
+            Self::Many(v) => v.into_iter(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct FullApiResponseError {
-    code: String,
-    msg: String,
+    pub code: String,
+    pub msg: String,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
@@ -21,54 +40,53 @@ pub enum ErrorWrapper {
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct QueryResult {
-    success: bool,
-    numpods: i32,
-    datatypes: String,
-    timedout: String,
-    timedoutpods: String,
-    timing: f32,
-    parsetiming: f32,
-    parsetimedout: bool,
-    recalculate: String,
-    id: String,
-    parseidserver: Option<String>,
-    host: String,
-    server: String,
-    related: String,
-    version: String,
-    inputstring: String,
-    pods: Option<Vec<RawPod>>,
-    sources: Option<SourcesWrapper>,
-    assumptions: Option<Vec<Assumption>>,
-    error: ErrorWrapper,
+    pub success: bool,
+    pub numpods: i32,
+    pub datatypes: String,
+    pub timedout: String,
+    pub timedoutpods: String,
+    pub timing: f32,
+    pub parsetiming: f32,
+    pub parsetimedout: bool,
+    pub recalculate: String,
+    pub id: String,
+    pub parseidserver: Option<String>,
+    pub host: String,
+    pub server: String,
+    pub related: String,
+    pub version: String,
+    pub inputstring: String,
+    pub pods: Option<Vec<RawPod>>,
+    pub sources: Option<OneOrMany<Source>>,
+    pub assumptions: Option<Vec<Assumption>>,
+    pub warnings: Option<OneOrMany<Warnings>>,
+    pub didyoumeans: Option<OneOrMany<DidYouMean>>,
+    pub tips: Option<OneOrMany<Tip>>,
+    pub languagemsg: Option<LanguageMsg>,
+    pub futuretopic: Option<FutureTopic>,
+    pub examplepage: Option<ExamplePage>,
+    pub error: ErrorWrapper,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Assumption {
-    r#type: String,
-    word: Option<String>,
-    desc: Option<String>,
-    current: Option<String>,
-    template: Option<String>,
-    count: i32,
-    values: AssumptionValuesWrapper,
-}
-
-#[derive(Debug, Clone, PartialEq, Deserialize)]
-#[serde(untagged)]
-pub enum AssumptionValuesWrapper {
-    Single(AssumptionValue),
-    Multi(Vec<AssumptionValue>),
+    pub r#type: String,
+    pub word: Option<String>,
+    pub desc: Option<String>,
+    pub current: Option<String>,
+    pub template: Option<String>,
+    pub count: i32,
+    pub values: OneOrMany<AssumptionValue>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct AssumptionValue {
-    name: String,
-    desc: String,
-    valid: Option<BoolOrText>,
-    input: String,
+    pub name: String,
+    pub desc: String,
+    pub valid: Option<BoolOrText>,
+    pub input: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -78,421 +96,117 @@ pub enum BoolOrText {
     Text(String),
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Source {
+    pub url: String,
+    pub text: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(untagged)]
-pub enum SourcesWrapper {
-    Single(Source),
-    Multi(Vec<Source>),
+pub enum F32OrString {
+    Num(f32),
+    Text(String),
+}
+
+impl F32OrString {
+    pub fn get_val(&self) -> f32 {
+        match self {
+            Self::Num(n) => *n,
+            Self::Text(t) => t.parse().unwrap_or(0.0),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
-pub struct Source {
-    url: String,
-    text: String,
+pub struct Warnings {
+    pub spellcheck: Option<OneOrMany<SpellcheckWarning>>,
+    pub delimiters: Option<DelimitersWarning>,
+    pub translation: Option<TranslationWarning>,
+    pub reinterpret: Option<ReinterpretWarning>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpellcheckWarning {
+    pub word: String,
+    pub suggestion: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DelimitersWarning {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TranslationWarning {
+    pub phrase: String,
+    pub trans: String,
+    pub lang: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReinterpretWarning {
+    pub text: String,
+    pub new: String,
+    pub score: Option<F32OrString>,
+    pub level: Option<String>,
+    pub alternative: Option<OneOrMany<DidYouMean>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DidYouMean {
+    pub score: F32OrString,
+    pub level: String,
+    pub val: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tip {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LanguageMsg {
+    pub english: String,
+    pub other: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FutureTopic {
+    pub topic: String,
+    pub msg: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExamplePage {
+    pub category: String,
+    pub url: String,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct RawApiResponse {
-    queryresult: QueryResult,
+    pub queryresult: QueryResult,
 }
 
 #[test]
 fn raw_api_response_deserialize() {
-    let a: RawApiResponse = serde_json::from_str(
-        r#"{
-	"queryresult":{
-		"success":false,
-		"numpods":0,
-		"datatypes":"",
-		"timedout":"",
-		"timedoutpods":"",
-		"timing":1.9e-2,
-		"parsetiming":0.0,
-		"parsetimedout":false,
-		"recalculate":"",
-		"id":"",
-		"parseidserver":"5",
-		"host":"https:\/\/www6b3.wolframalpha.com",
-		"server":"5",
-		"related":"",
-		"version":"2.6",
-		"inputstring":"population france",
-		"error":{
-			"code":"1",
-			"msg":"Invalid appid"
-		}
-	}
-}"#,
-    )
-    .unwrap();
+    let a: RawApiResponse = serde_json::from_str(crate::testing::fixtures::INVALID_APPID).unwrap();
 
-    let b: RawApiResponse = serde_json::from_str(
-r#"{
-	"queryresult":{
-		"success":true,
-		"error":false,
-		"numpods":8,
-		"datatypes":"City,Country",
-		"timedout":"",
-		"timedoutpods":"",
-		"timing":2.507,
-		"parsetiming":0.153,
-		"parsetimedout":false,
-		"recalculate":"",
-		"id":"MSP8893186885ghge360i170000348ee64i84058ib8",
-		"host":"https:\/\/www6b3.wolframalpha.com",
-		"server":"13",
-		"related":"https:\/\/www6b3.wolframalpha.com\/api\/v1\/relatedQueries.jsp?id=MSPa8894186885ghge360i1700005c82257d61176ihh4222294402565530544",
-		"version":"2.6",
-		"inputstring":"population france",
-		"pods":[
-			{
-				"title":"Input interpretation",
-				"scanner":"Identity",
-				"id":"Input",
-				"position":100,
-				"error":false,
-				"numsubpods":1,
-				"subpods":[
-					{
-						"title":"",
-						"img":{
-							"src":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP8895186885ghge360i17000017e20d850ch73bd1?MSPStoreType=image\/gif&s=13",
-							"alt":"France | population",
-							"title":"France | population",
-							"width":146,
-							"height":23,
-							"type":"Grid",
-							"themes":"1,2,3,4,5,6,7,8,9,10,11,12",
-							"colorinvertable":true,
-							"contenttype":"image\/gif"
-						},
-						"plaintext":"France | population"
-					}
-				],
-				"expressiontypes":{
-					"name":"Grid"
-				}
-			},
-			{
-				"title":"Result",
-				"scanner":"Data",
-				"id":"Result",
-				"position":200,
-				"error":false,
-				"numsubpods":1,
-				"primary":true,
-				"subpods":[
-					{
-						"title":"",
-						"microsources":{
-							"microsource":"CountryData"
-						},
-						"datasources":{
-							"datasource":"UNData"
-						},
-						"img":{
-							"src":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP8896186885ghge360i1700001c1b70ff10793708?MSPStoreType=image\/gif&s=13",
-							"alt":"65.3 million people (world rank: 22nd) (2020 estimate)",
-							"title":"65.3 million people (world rank: 22nd) (2020 estimate)",
-							"width":332,
-							"height":19,
-							"type":"Default",
-							"themes":"1,2,3,4,5,6,7,8,9,10,11,12",
-							"colorinvertable":true,
-							"contenttype":"image\/gif"
-						},
-						"plaintext":"65.3 million people (world rank: 22nd) (2020 estimate)"
-					}
-				],
-				"expressiontypes":{
-					"name":"Default"
-				}
-			},
-			{
-				"title":"Recent population history",
-				"scanner":"Data",
-				"id":"RecentHistory:Population:CountryData",
-				"position":300,
-				"error":false,
-				"numsubpods":1,
-				"subpods":[
-					{
-						"title":"",
-						"microsources":{
-							"microsource":"CountryData"
-						},
-						"datasources":{
-							"datasource":"UNData"
-						},
-						"img":{
-							"src":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP8897186885ghge360i1700003406hg4f8f5hi9fi?MSPStoreType=image\/gif&s=13",
-							"alt":"Recent population history",
-							"title":"",
-							"width":383,
-							"height":160,
-							"type":"TimeSeriesPlot_1",
-							"themes":"1,2,3,4,5,6,7,8,9,10,11,12",
-							"colorinvertable":true,
-							"contenttype":"image\/gif"
-						},
-						"plaintext":""
-					}
-				],
-				"expressiontypes":{
-					"name":"Default"
-				},
-				"states":[
-					{
-						"name":"Show projections",
-						"input":"RecentHistory:Population:CountryData__Show projections"
-					},
-					{
-						"name":"Log scale",
-						"input":"RecentHistory:Population:CountryData__Log scale"
-					}
-				]
-			},
-			{
-				"title":"Long-term population history",
-				"scanner":"Data",
-				"id":"LongTermHistory:Population:CountryData",
-				"position":400,
-				"error":false,
-				"numsubpods":1,
-				"subpods":[
-					{
-						"title":"",
-						"microsources":{
-							"microsource":"CountryData"
-						},
-						"datasources":{
-							"datasource":"UNData"
-						},
-						"img":{
-							"src":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP8898186885ghge360i1700004c839g212ab5891h?MSPStoreType=image\/gif&s=13",
-							"alt":"Long-term population history",
-							"title":"",
-							"width":546,
-							"height":230,
-							"type":"TimeSeriesPlot_1",
-							"themes":"1,2,3,4,5,6,7,8,9,10,11,12",
-							"colorinvertable":true,
-							"contenttype":"image\/gif"
-						},
-						"plaintext":""
-					}
-				],
-				"expressiontypes":{
-					"name":"Default"
-				},
-				"states":[
-					{
-						"name":"Show projections",
-						"input":"LongTermHistory:Population:CountryData__Show projections"
-					},
-					{
-						"name":"Log scale",
-						"input":"LongTermHistory:Population:CountryData__Log scale"
-					}
-				]
-			},
-			{
-				"title":"Demographics",
-				"scanner":"Data",
-				"id":"DemographicProperties:CountryData",
-				"position":500,
-				"error":false,
-				"numsubpods":1,
-				"subpods":[
-					{
-						"title":"",
-						"microsources":{
-							"microsource":"CountryData"
-						},
-						"datasources":{
-							"datasource":[
-								"UNData",
-								"CIAFactbook"
-							]
-						},
-						"img":{
-							"src":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP8899186885ghge360i17000010b0ii2hda0h4eb9?MSPStoreType=image\/gif&s=13",
-							"alt":"population | 65.3 million people (world rank: 22nd) (2020 estimate)\npopulation density | 119 people\/km^2 (people per square kilometer) (world rank: 100th) (2019 estimate)\npopulation growth | 0.14 %\/yr (world rank: 198th) (2019 estimate)\nlife expectancy | 82.4 years (world rank: 20th) (2021 estimate)\nmedian age | 41.2 years (world rank: 26th) (2015 estimate)",
-							"title":"population | 65.3 million people (world rank: 22nd) (2020 estimate)\npopulation density | 119 people\/km^2 (people per square kilometer) (world rank: 100th) (2019 estimate)\npopulation growth | 0.14 %\/yr (world rank: 198th) (2019 estimate)\nlife expectancy | 82.4 years (world rank: 20th) (2021 estimate)\nmedian age | 41.2 years (world rank: 26th) (2015 estimate)",
-							"width":546,
-							"height":187,
-							"type":"Grid",
-							"themes":"1,2,3,4,5,6,7,8,9,10,11,12",
-							"colorinvertable":true,
-							"contenttype":"image\/gif"
-						},
-						"plaintext":"population | 65.3 million people (world rank: 22nd) (2020 estimate)\npopulation density | 119 people\/km^2 (people per square kilometer) (world rank: 100th) (2019 estimate)\npopulation growth | 0.14 %\/yr (world rank: 198th) (2019 estimate)\nlife expectancy | 82.4 years (world rank: 20th) (2021 estimate)\nmedian age | 41.2 years (world rank: 26th) (2015 estimate)"
-					}
-				],
-				"expressiontypes":{
-					"name":"Grid"
-				},
-				"states":[
-					{
-						"name":"Show rates",
-						"input":"DemographicProperties:CountryData__Show rates"
-					},
-					{
-						"name":"Show distribution",
-						"input":"DemographicProperties:CountryData__Show distribution"
-					},
-					{
-						"name":"Show non-metric",
-						"input":"DemographicProperties:CountryData__Show non-metric"
-					}
-				],
-				"infos":{
-					"units":[
-						{
-							"short":"%\/yr",
-							"long":"percent per year"
-						},
-						{
-							"src":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP8900186885ghge360i17000063feh8ed72e9aee5?MSPStoreType=image\/gif&s=13",
-							"width":"164",
-							"height":"27"
-						}
-					]
-				}
-			},
-			{
-				"title":"Age distribution",
-				"scanner":"Data",
-				"id":"AgeDistributionPyramidGraphic:AgeDistributionData",
-				"position":600,
-				"error":false,
-				"numsubpods":1,
-				"subpods":[
-					{
-						"title":"",
-						"img":{
-							"src":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP8901186885ghge360i170000246c1658be21de2d?MSPStoreType=image\/gif&s=13",
-							"alt":"Age distribution",
-							"title":"",
-							"width":546,
-							"height":467,
-							"type":"Default",
-							"themes":"1,2,3,4,5,6,7,8,9,10,11,12",
-							"colorinvertable":true,
-							"contenttype":"image\/gif"
-						},
-						"plaintext":""
-					}
-				],
-				"expressiontypes":{
-					"name":"Default"
-				}
-			},
-			{
-				"title":"Largest cities",
-				"scanner":"Data",
-				"id":"LargestCities:CountryData",
-				"position":700,
-				"error":false,
-				"numsubpods":1,
-				"subpods":[
-					{
-						"title":"",
-						"microsources":{
-							"microsource":[
-								"CityData",
-								"CountryData"
-							]
-						},
-						"img":{
-							"src":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP8902186885ghge360i1700001hgi73egh8050dg1?MSPStoreType=image\/gif&s=13",
-							"alt":"city | population\nParis, Ile-de-France | 2.206 million people\nMarseille, Provence-Alpes-Cote-d'Azur | 855393 people\nLyon, Auvergne-Rhône-Alpes | 506615 people\nToulouse, Occitanie | 471941 people\nNice, Provence-Alpes-Cote-d'Azur | 342522 people\n(2004, 2007, 2013, 2014, 2015, and 2017 estimates)",
-							"title":"city | population\nParis, Ile-de-France | 2.206 million people\nMarseille, Provence-Alpes-Cote-d'Azur | 855393 people\nLyon, Auvergne-Rhône-Alpes | 506615 people\nToulouse, Occitanie | 471941 people\nNice, Provence-Alpes-Cote-d'Azur | 342522 people\n(2004, 2007, 2013, 2014, 2015, and 2017 estimates)",
-							"width":429,
-							"height":207,
-							"type":"Grid",
-							"themes":"1,2,3,4,5,6,7,8,9,10,11,12",
-							"colorinvertable":true,
-							"contenttype":"image\/gif"
-						},
-						"plaintext":"city | population\nParis, Ile-de-France | 2.206 million people\nMarseille, Provence-Alpes-Cote-d'Azur | 855393 people\nLyon, Auvergne-Rhône-Alpes | 506615 people\nToulouse, Occitanie | 471941 people\nNice, Provence-Alpes-Cote-d'Azur | 342522 people\n(2004, 2007, 2013, 2014, 2015, and 2017 estimates)"
-					}
-				],
-				"expressiontypes":{
-					"name":"Grid"
-				},
-				"states":[
-					{
-						"name":"More",
-						"input":"LargestCities:CountryData__More"
-					}
-				]
-			},
-			{
-				"title":"Comparisons",
-				"scanner":"Unit",
-				"id":"ComparisonAsPersonCount",
-				"position":800,
-				"error":false,
-				"numsubpods":2,
-				"subpods":[
-					{
-						"title":"",
-						"img":{
-							"src":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP8903186885ghge360i1700003cg4ag84g3d7d322?MSPStoreType=image\/gif&s=13",
-							"alt":" ≈ 0.62 × current population of Philippines ( 104.9 million people )",
-							"title":" ≈ 0.62 × current population of Philippines ( 104.9 million people )",
-							"width":390,
-							"height":19,
-							"type":"Default",
-							"themes":"1,2,3,4,5,6,7,8,9,10,11,12",
-							"colorinvertable":true,
-							"contenttype":"image\/gif"
-						},
-						"plaintext":" ≈ 0.62 × current population of Philippines ( 104.9 million people )"
-					},
-					{
-						"title":"",
-						"img":{
-							"src":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP8904186885ghge360i1700004c7e0g0dfeb9fedf?MSPStoreType=image\/gif&s=13",
-							"alt":" ≈ 0.79 × current population of Germany ( 82.11 million people )",
-							"title":" ≈ 0.79 × current population of Germany ( 82.11 million people )",
-							"width":379,
-							"height":19,
-							"type":"Default",
-							"themes":"1,2,3,4,5,6,7,8,9,10,11,12",
-							"colorinvertable":true,
-							"contenttype":"image\/gif"
-						},
-						"plaintext":" ≈ 0.79 × current population of Germany ( 82.11 million people )"
-					}
-				],
-				"expressiontypes":[
-					{
-						"name":"Default"
-					},
-					{
-						"name":"Default"
-					}
-				]
-			}
-		],
-		"sources":[
-			{
-				"url":"https:\/\/www6b3.wolframalpha.com\/sources\/CityDataSourceInformationNotes.html",
-				"text":"City data"
-			},
-			{
-				"url":"https:\/\/www6b3.wolframalpha.com\/sources\/CountryDataSourceInformationNotes.html",
-				"text":"Country data"
-			}
-		]
-	}
-}"#
-    ).unwrap();
+    let b: RawApiResponse = serde_json::from_str(crate::testing::fixtures::POPULATION_FRANCE).unwrap();
 
     let c: RawApiResponse = serde_json::from_str(
 r#"{
@@ -3983,4 +3697,251 @@ r#"{
 						]
 					}
 				}"#).unwrap();
+
+    for r in [a, b, c, d, e, f, g, h, i, j, k, l, m] {
+        let _: crate::response::QueryResult = r.into();
+    }
+}
+
+#[test]
+fn raw_subpod_mathml_deserialize() {
+    let a: RawSubPod = serde_json::from_str(
+        r#"{
+	"title":"",
+	"mathml":"<math xmlns='http:\/\/www.w3.org\/1998\/Math\/MathML'\n    mathematica:form='StandardForm'\n    xmlns:mathematica='http:\/\/www.wolfram.com\/XML\/'>\n <mrow>\n  <mi>x<\/mi>\n  <mo>&#10869;<\/mo>\n  <mn>2<\/mn>\n <\/mrow>\n<\/math>",
+	"minput":"Solve[x^2 - 4 == 0 && x > 0, x]",
+	"moutput":"{{x -> 2}}"
+}"#,
+    )
+    .unwrap();
+
+    let a: crate::response::SubPod = a.into();
+    assert_eq!(a.img, None);
+    assert_eq!(a.plaintext, None);
+    assert!(a.mathml.unwrap().contains("<mn>2</mn>"));
+    assert_eq!(a.minput.as_deref(), Some("Solve[x^2 - 4 == 0 && x > 0, x]"));
+    assert_eq!(a.moutput.as_deref(), Some("{{x -> 2}}"));
+}
+
+#[test]
+fn raw_pod_sounds_deserialize() {
+    let a: RawPod = serde_json::from_str(
+        r#"{
+	"title":"Music notation",
+	"scanner":"Music",
+	"id":"MusicNotation",
+	"position":200,
+	"error":false,
+	"numsubpods":1,
+	"subpods":[
+		{
+			"title":"",
+			"plaintext":"",
+			"cell":"Cell[BoxData[GraphicsBox[{}]], \"Output\"]"
+		}
+	],
+	"expressiontypes":{
+		"name":"Default"
+	},
+	"sounds":{
+		"count":1,
+		"sound":{
+			"url":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP3571i2f5ig0e7ecf5a1000046a0ic0b55ccf9gb?MSPStoreType=audio\/midi&s=19",
+			"type":"audio\/midi"
+		}
+	}
+}"#,
+    )
+    .unwrap();
+
+    let a: crate::response::Pod = a.into();
+    assert_eq!(a.sounds.len(), 1);
+    assert_eq!(a.sounds[0].sound_type, "audio/midi");
+    assert!(a.subpods[0].cell.as_ref().unwrap().is_string());
+}
+
+#[test]
+fn raw_api_response_no_result_deserialize() {
+    use crate::response::{QueryOutcome, Warning};
+
+    let a: RawApiResponse = serde_json::from_str(
+        r#"{
+	"queryresult":{
+		"success":false,
+		"error":false,
+		"numpods":0,
+		"datatypes":"",
+		"timedout":"",
+		"timedoutpods":"",
+		"timing":0.9,
+		"parsetiming":0.3,
+		"parsetimedout":false,
+		"recalculate":"",
+		"id":"",
+		"host":"https:\/\/www6b3.wolframalpha.com",
+		"server":"6",
+		"related":"",
+		"version":"2.6",
+		"inputstring":"wehter in bostn",
+		"didyoumeans":[
+			{
+				"score":"0.365",
+				"level":"medium",
+				"val":"bostn"
+			},
+			{
+				"score":"0.415",
+				"level":"medium",
+				"val":"weather in"
+			}
+		],
+		"tips":{
+			"text":"Check your spelling, and use English"
+		},
+		"warnings":{
+			"spellcheck":{
+				"word":"wehter",
+				"suggestion":"weather",
+				"text":"Interpreting \"wehter\" as \"weather\""
+			}
+		},
+		"examplepage":{
+			"category":"Weather",
+			"url":"http:\/\/www.wolframalpha.com\/examples\/Weather-content.html"
+		}
+	}
+}"#,
+    )
+    .unwrap();
+
+    let b: RawApiResponse = serde_json::from_str(
+        r#"{
+	"queryresult":{
+		"success":false,
+		"error":false,
+		"numpods":0,
+		"datatypes":"",
+		"timedout":"",
+		"timedoutpods":"",
+		"timing":0.4,
+		"parsetiming":0.1,
+		"parsetimedout":false,
+		"recalculate":"",
+		"id":"",
+		"host":"https:\/\/www6b3.wolframalpha.com",
+		"server":"6",
+		"related":"",
+		"version":"2.6",
+		"inputstring":"microsoft windows",
+		"futuretopic":{
+			"topic":"Microsoft Windows",
+			"msg":"Development of this topic is under investigation..."
+		},
+		"languagemsg":{
+			"english":"Wolfram|Alpha does not yet support German.",
+			"other":"Wolfram|Alpha versteht noch kein Deutsch."
+		}
+	}
+}"#,
+    )
+    .unwrap();
+
+    let a: crate::response::QueryResult = a.into();
+    match a.outcome() {
+        QueryOutcome::DidYouMean(d) => {
+            assert_eq!(d[0].val, "weather in");
+            assert_eq!(d[1].score, 0.365);
+        }
+        o => panic!("unexpected outcome {:?}", o),
+    }
+    assert_eq!(a.best_did_you_mean(0.4).unwrap().val, "weather in");
+    assert_eq!(a.best_did_you_mean(0.5), None);
+    assert_eq!(a.tips, vec!["Check your spelling, and use English"]);
+    assert!(matches!(&a.warnings[..], [Warning::Spellcheck(s)] if s.suggestion == "weather"));
+    assert_eq!(a.example_page.unwrap().category, "Weather");
+
+    let b: crate::response::QueryResult = b.into();
+    assert!(matches!(b.outcome(), QueryOutcome::FutureTopic(f) if f.topic == "Microsoft Windows"));
+    assert!(b.language_msg.is_some());
+    assert_eq!(b.best_did_you_mean(0.0), None);
+}
+
+#[test]
+fn raw_api_response_reinterpret_deserialize() {
+    let a: RawApiResponse = serde_json::from_str(
+        r#"{
+	"queryresult":{
+		"success":true,
+		"error":false,
+		"numpods":0,
+		"datatypes":"",
+		"timedout":"",
+		"timedoutpods":"",
+		"timing":1.2,
+		"parsetiming":0.5,
+		"parsetimedout":false,
+		"recalculate":"",
+		"id":"",
+		"host":"https:\/\/www6b3.wolframalpha.com",
+		"server":"6",
+		"related":"",
+		"version":"2.6",
+		"inputstring":"kitty danger",
+		"warnings":{
+			"reinterpret":{
+				"text":"Using closest Wolfram|Alpha interpretation:",
+				"new":"danger",
+				"score":"0.416667",
+				"level":"medium",
+				"alternative":[
+					{
+						"score":"0.385",
+						"level":"medium",
+						"val":"kitty"
+					}
+				]
+			}
+		}
+	}
+}"#,
+    )
+    .unwrap();
+
+    let a: crate::response::QueryResult = a.into();
+    let r = a.reinterpretation.unwrap();
+    assert_eq!(r.original, "kitty danger");
+    assert_eq!(r.new, "danger");
+    assert_eq!(r.alternatives, vec!["kitty"]);
+}
+
+#[test]
+fn raw_info_units_deserialize() {
+    let a: RawInfo = serde_json::from_str(
+        r#"{
+	"units":[
+		[
+			{
+				"short":"a",
+				"long":"Earth equatorial radii"
+			},
+			{
+				"short":"km",
+				"long":"kilometers"
+			}
+		],
+		{
+			"src":"https:\/\/www6b3.wolframalpha.com\/Calculate\/MSP\/MSP58541cg68e4id1204i8g00005cada8iaeaha82ei?MSPStoreType=image\/gif&s=19",
+			"width":"181",
+			"height":"49"
+		}
+	]
+}"#,
+    )
+    .unwrap();
+
+    let a: crate::response::Info = a.into();
+    let units = a.units.unwrap();
+    assert_eq!(units.units.len(), 2);
+    assert_eq!(units.units[1].long, "kilometers");
+    assert_eq!(units.legend.unwrap().width, 181);
 }
@@ -1,33 +1,39 @@
+use super::OneOrMany;
 use serde::Deserialize;
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct RawPod {
-    title: String,
-    scanner: String,
-    id: String,
-    position: i32,
-    error: bool,
-    numsubpods: i32,
-    subpods: Vec<RawSubPod>,
-    expressiontypes: RawExpressionTypesWrapper,
-    states: Option<Vec<RawStateWrapper>>,
-    infos: Option<RawInfo>,
-    primary: Option<bool>,
-    definitions: Option<RawDefinitionsWrapper>,
+    pub title: String,
+    pub scanner: String,
+    pub id: String,
+    pub position: i32,
+    pub error: bool,
+    pub numsubpods: i32,
+    pub subpods: Vec<RawSubPod>,
+    pub expressiontypes: OneOrMany<RawExpressionType>,
+    pub states: Option<Vec<RawStateWrapper>>,
+    pub infos: Option<RawInfo>,
+    pub primary: Option<bool>,
+    pub definitions: Option<OneOrMany<RawDefinition>>,
+    pub sounds: Option<RawSounds>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct RawSubPod {
-    title: String,
-    primary: Option<bool>,
-    imagesource: Option<String>,
-    microsources: Option<RawMicroSource>,
-    datasources: Option<RawDataSource>,
-    img: RawImage,
-    plaintext: String,
-    infos: Option<RawInfo>,
+    pub title: String,
+    pub primary: Option<bool>,
+    pub imagesource: Option<String>,
+    pub microsources: Option<RawMicroSource>,
+    pub datasources: Option<RawDataSource>,
+    pub img: Option<RawImage>,
+    pub plaintext: Option<String>,
+    pub mathml: Option<String>,
+    pub minput: Option<String>,
+    pub moutput: Option<String>,
+    pub cell: Option<serde_json::Value>,
+    pub infos: Option<RawInfo>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -60,43 +66,22 @@ impl I32OrString {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
-#[serde(untagged)]
-pub enum RawExpressionTypesWrapper {
-    Single(RawExpressionType),
-    Multi(Vec<RawExpressionType>),
-}
-
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct RawExpressionType {
-    name: String,
-}
-
-#[derive(Debug, Clone, Deserialize, PartialEq)]
-#[serde(untagged)]
-pub enum RawMicroSourceWrapper {
-    Single(String),
-    Multi(Vec<String>),
+    pub name: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct RawMicroSource {
-    microsource: RawMicroSourceWrapper,
-}
-
-#[derive(Debug, Clone, Deserialize, PartialEq)]
-#[serde(untagged)]
-pub enum RawDataSourceWrapper {
-    Single(String),
-    Multi(Vec<String>),
+    pub microsource: OneOrMany<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct RawDataSource {
-    datasource: RawDataSourceWrapper,
+    pub datasource: OneOrMany<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -129,25 +114,7 @@ pub struct RawInfo {
     pub units: Option<Vec<RawUnitsWrapper>>,
     pub text: Option<String>,
     pub img: Option<RawImage>,
-    pub links: Option<RawLinksWrapper>,
-}
-
-#[derive(Debug, Clone, PartialEq, Deserialize)]
-#[serde(untagged)]
-pub enum RawLinksWrapper {
-    Single(RawLink),
-    Multi(Vec<RawLink>),
-}
-
-impl IntoIterator for RawLinksWrapper {
-    type Item = RawLink;
-    type IntoIter = std::vec::IntoIter<Self::Item>;
-    fn into_iter(self) -> Self::IntoIter {
-        match self {
-            Self::Single(r) => vec![r].into_iter(),
-            Self::Multi(v) => v.into_iter(),
-        }
-    }
+    pub links: Option<OneOrMany<RawLink>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -161,28 +128,10 @@ pub struct RawLink {
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(untagged)]
 pub enum RawUnitsWrapper {
-    MeasurementUnits(RawMeasurementUnitsWrapper),
+    MeasurementUnits(OneOrMany<RawMeasurementUnit>),
     Src(RawUnitSource),
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
-#[serde(untagged)]
-pub enum RawMeasurementUnitsWrapper {
-    Single(RawMeasurementUnit),
-    Multi(Vec<RawMeasurementUnit>),
-}
-
-impl IntoIterator for RawMeasurementUnitsWrapper {
-    type Item = RawMeasurementUnit;
-    type IntoIter = std::vec::IntoIter<Self::Item>;
-    fn into_iter(self) -> Self::IntoIter {
-        match self {
-            Self::Multi(v) => v.into_iter(),
-            Self::Single(i) => vec![i].into_iter(),
-        }
-    }
-}
-
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct RawMeasurementUnit {
@@ -196,20 +145,27 @@ pub struct RawMeasurementUnit {
 #[serde(deny_unknown_fields)]
 pub struct RawUnitSource {
     pub src: String,
-    pub width: String,
-    pub height: String,
+    pub width: I32OrString,
+    pub height: I32OrString,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct RawDefinition {
-    word: String,
-    desc: String,
+    pub word: String,
+    pub desc: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
-#[serde(untagged)]
-pub enum RawDefinitionsWrapper {
-    Single(RawDefinition),
-    Multi(Vec<RawDefinition>),
+#[serde(deny_unknown_fields)]
+pub struct RawSounds {
+    pub count: Option<i32>,
+    pub sound: OneOrMany<RawSound>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RawSound {
+    pub url: String,
+    pub r#type: String,
 }
@@ -0,0 +1,126 @@
+/// A grid parsed from the pipe delimited plaintext wolframalpha uses for
+/// tabular pods, e.g. `"population | 65.3 million people"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    /// The column headers, present when the first row has an empty leading
+    /// cell, which is how wolframalpha marks a header row
+    pub header: Option<Vec<String>>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Splits a line on `|` separators. A pipe only counts as a separator when
+/// it is surrounded by whitespace or the ends of the line, so plaintext such
+/// as `|x|` is left alone.
+fn split_cells(line: &str) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut cells = vec![];
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        let before = i == 0 || chars[i - 1].is_whitespace();
+        let after = i + 1 == chars.len() || chars[i + 1].is_whitespace();
+
+        if c == '|' && before && after {
+            cells.push(current.trim().to_string());
+            current.clear();
+        } else {
+            current.push(c);
+        }
+    }
+    cells.push(current.trim().to_string());
+
+    cells
+}
+
+fn escape_csv(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+impl Table {
+    /// Parses pipe delimited plaintext into a table. Every row is padded to
+    /// the width of the widest row. Returns `None` if no line has more than
+    /// one cell.
+    pub fn parse(plaintext: &str) -> Option<Table> {
+        let mut rows: Vec<Vec<String>> = plaintext
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(split_cells)
+            .collect();
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if width < 2 {
+            return None;
+        }
+
+        for row in rows.iter_mut() {
+            row.resize(width, String::new());
+        }
+
+        let header = if rows.len() > 1 && rows[0][0].is_empty() {
+            Some(rows.remove(0))
+        } else {
+            None
+        };
+
+        Some(Table { header, rows })
+    }
+
+    /// Returns all rows of the table, including the header row if there is one
+    pub fn to_grid(&self) -> Vec<Vec<String>> {
+        self.header
+            .iter()
+            .chain(self.rows.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// Formats the table as CSV, with the header row first if there is one
+    pub fn to_csv(&self) -> String {
+        let mut res = String::new();
+
+        for row in self.header.iter().chain(self.rows.iter()) {
+            let line: Vec<String> = row.iter().map(|c| escape_csv(c)).collect();
+            res.push_str(&line.join(","));
+            res.push_str("\r\n");
+        }
+
+        res
+    }
+}
+
+#[test]
+fn table_parse() {
+    assert_eq!(Table::parse("65.3 million people"), None);
+    assert_eq!(Table::parse("|x| = 3"), None);
+
+    let t = Table::parse("France | population").unwrap();
+    assert_eq!(t.header, None);
+    assert_eq!(t.rows, vec![vec!["France", "population"]]);
+
+    let t = Table::parse(
+        " | GDP | population\nFrance | $2.6 trillion | 65.3 million\nSweden | $541 billion\n",
+    )
+    .unwrap();
+    assert_eq!(
+        t.header,
+        Some(vec!["".into(), "GDP".into(), "population".into()])
+    );
+    assert_eq!(
+        t.rows,
+        vec![
+            vec!["France", "$2.6 trillion", "65.3 million"],
+            vec!["Sweden", "$541 billion", ""],
+        ]
+    );
+    assert_eq!(t.to_grid().len(), 3);
+}
+
+#[test]
+fn table_to_csv() {
+    let t = Table::parse(" | value\n\"a\" | 1,000\n").unwrap();
+    assert_eq!(t.to_csv(), ",value\r\n\"\"\"a\"\"\",\"1,000\"\r\n");
+}
@@ -0,0 +1,153 @@
+use super::prelude::*;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::time::Duration;
+
+/// Retries 429 and 5xx statuses, except 501 which the `v1` apis use for
+/// questions they can't answer
+pub fn default_retryable_status(status: u16) -> bool {
+    status == 429 || (status >= 500 && status != 501)
+}
+
+/// Retries timeouts, failed connections and connections that were closed
+/// early
+pub fn default_retryable_error(error: &(dyn Error + 'static)) -> bool {
+    let mut current = Some(error);
+    while let Some(e) = current {
+        #[cfg(feature = "reqwest")]
+        if let Some(e) = e.downcast_ref::<reqwest::Error>() {
+            if e.is_timeout() || e.is_connect() {
+                return true;
+            }
+        }
+        if let Some(e) = e.downcast_ref::<io::Error>() {
+            if matches!(
+                e.kind(),
+                io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::TimedOut
+                    | io::ErrorKind::UnexpectedEof
+            ) {
+                return true;
+            }
+        }
+        current = e.source();
+    }
+    false
+}
+
+/// Controls how a [`Client`](crate::Client) retries api calls that failed
+/// for reasons that are likely to go away, see [`Client::with_retry`]
+///
+/// [`Client::with_retry`]: crate::Client::with_retry
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts made in total, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// The delay is multiplied by this after every retry. Should be at
+    /// least 1, delays that come out negative or not a number are replaced
+    /// with `max_backoff`.
+    pub multiplier: f64,
+    /// Upper bound of the delay
+    pub max_backoff: Duration,
+    /// Randomizes every delay to between zero and its full length, so
+    /// clients that failed at the same time don't retry at the same time
+    pub jitter: bool,
+    /// Decides which response statuses are retried
+    pub retryable_status: fn(u16) -> bool,
+    /// Decides which transport errors are retried
+    pub retryable_error: fn(&(dyn Error + 'static)) -> bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            multiplier: 2.0,
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retryable_status: default_retryable_status,
+            retryable_error: default_retryable_error,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before retry number `retry`, starting at 1, without jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.powi(retry.saturating_sub(1) as i32);
+        let secs = self.initial_backoff.as_secs_f64() * factor;
+        // false for the negative and NaN delays of odd multipliers
+        if secs >= 0.0 && secs < self.max_backoff.as_secs_f64() {
+            Duration::from_secs_f64(secs)
+        } else {
+            self.max_backoff
+        }
+    }
+
+    /// Decides if `attempt`, which ended with `outcome`, is retried, and
+    /// after how long
+    pub(crate) fn retry_delay(
+        &self,
+        attempt: u32,
+        outcome: Result<u16, &(dyn Error + 'static)>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let retryable = match outcome {
+            Ok(status) => (self.retryable_status)(status),
+            Err(e) => (self.retryable_error)(e),
+        };
+        if !retryable {
+            return None;
+        }
+
+        let delay = self.backoff(attempt);
+        if self.jitter {
+            let random = RandomState::new().build_hasher().finish();
+            Some(delay.mul_f64(random as f64 / u64::MAX as f64))
+        } else {
+            Some(delay)
+        }
+    }
+}
+
+#[test]
+fn retry_policy_delays() {
+    let policy = RetryPolicy {
+        jitter: false,
+        max_attempts: 5,
+        max_backoff: Duration::from_secs(3),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        policy.retry_delay(1, Ok(503)),
+        Some(Duration::from_millis(500))
+    );
+    assert_eq!(policy.retry_delay(2, Ok(500)), Some(Duration::from_secs(1)));
+    assert_eq!(policy.retry_delay(3, Ok(429)), Some(Duration::from_secs(2)));
+    assert_eq!(policy.retry_delay(4, Ok(503)), Some(Duration::from_secs(3)));
+    assert_eq!(policy.retry_delay(5, Ok(503)), None);
+
+    assert_eq!(policy.retry_delay(1, Ok(501)), None);
+    assert_eq!(policy.retry_delay(1, Ok(404)), None);
+
+    let negative = RetryPolicy {
+        multiplier: -2.0,
+        ..policy.clone()
+    };
+    assert_eq!(negative.backoff(2), Duration::from_secs(3));
+    assert_eq!(negative.backoff(3), Duration::from_secs(2));
+
+    let reset = io::Error::from(io::ErrorKind::ConnectionReset);
+    assert!(policy.retry_delay(1, Err(&reset)).is_some());
+    let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+    assert!(policy.retry_delay(1, Err(&denied)).is_none());
+}
@@ -0,0 +1,72 @@
+use std::fmt;
+
+/// A signing secret, kept out of `Debug` output
+#[derive(Clone)]
+pub(crate) struct SigningSecret(String);
+
+impl SigningSecret {
+    pub fn new(secret: String) -> Self {
+        SigningSecret(secret)
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SigningSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SigningSecret(REDACTED)")
+    }
+}
+
+/// Computes the `sig` parameter of a signed request
+///
+/// The parameters, unencoded, are sorted by name and then by value, each
+/// one is written as its name directly followed by its value, and the
+/// result is prefixed with `secret`. The signature is the MD5 hash of that
+/// string as uppercase hex.
+///
+/// # Arguments
+///
+/// * `secret` - The signing secret of your wolframalpha application
+/// * `params` - Every parameter of the request except `sig`
+pub fn sign(secret: &str, params: &[(String, String)]) -> String {
+    let mut sorted: Vec<_> = params.iter().collect();
+    sorted.sort();
+
+    let mut canonical = secret.to_string();
+    for (name, value) in sorted {
+        canonical.push_str(name);
+        canonical.push_str(value);
+    }
+
+    format!("{:X}", md5::compute(canonical))
+}
+
+#[test]
+fn sign_known_vectors() {
+    let params = |p: &[(&str, &str)]| -> Vec<(String, String)> {
+        p.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    };
+
+    assert_eq!(
+        sign(
+            "SECRET",
+            &params(&[("appid", "DEMO"), ("input", "pi"), ("output", "json")])
+        ),
+        "8E672BAF5DA5BE83E4C960F173083F41"
+    );
+    // the order of the parameters doesn't matter, and values are signed
+    // unencoded
+    assert_eq!(
+        sign(
+            "SECRET",
+            &params(&[("units", "metric"), ("i", "1+1 = 2 😀"), ("appid", "DEMO")])
+        ),
+        "A8F5B587E20B957A55492914B1A73DBE"
+    );
+    assert_eq!(sign("", &[]), "D41D8CD98F00B204E9800998ECF8427E");
+}
@@ -0,0 +1,147 @@
+use crate::encoding::{redact_url, stable_hash};
+use crate::prelude::*;
+use crate::transport::{HttpResponse, HttpTransport};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Interaction {
+    url: String,
+    status: u16,
+}
+
+fn paths(dir: &Path, url: &str) -> (String, PathBuf, PathBuf) {
+    let redacted = redact_url(url);
+    let stem = stable_hash(&redacted);
+    (
+        redacted,
+        dir.join(format!("{}.json", stem)),
+        dir.join(format!("{}.body", stem)),
+    )
+}
+
+/// A transport that passes requests on to another transport and writes
+/// every request and response to a directory, to be replayed later by
+/// [`Replayer`]. The AppID is redacted from the recorded requests.
+///
+/// Each interaction is stored as a `.json` file with the url and status and
+/// a `.body` file with the raw response body.
+#[derive(Debug, Clone)]
+pub struct Recorder<T> {
+    dir: PathBuf,
+    inner: T,
+}
+
+impl<T: HttpTransport> Recorder<T> {
+    /// Records the requests made through `inner` into `dir`, which is
+    /// created if it doesn't exist
+    pub fn new(dir: impl Into<PathBuf>, inner: T) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Recorder { dir, inner })
+    }
+}
+
+impl<T: HttpTransport> HttpTransport for Recorder<T> {
+    fn get(
+        &self,
+        url: String,
+    ) -> BoxFuture<'_, Result<HttpResponse, Box<dyn Error + Send + Sync>>> {
+        Box::pin(async move {
+            let (redacted, meta_path, body_path) = paths(&self.dir, &url);
+            let response = self.inner.get(url).await?;
+
+            let meta = Interaction {
+                url: redacted,
+                status: response.status,
+            };
+            fs::write(meta_path, serde_json::to_vec_pretty(&meta)?)?;
+            fs::write(body_path, &response.body)?;
+
+            Ok(response)
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        self.inner.sleep(duration)
+    }
+}
+
+/// A transport that answers requests with the responses a [`Recorder`]
+/// wrote to a directory. Requests are matched by their url with the AppID
+/// redacted, so replaying works with any AppID. Requests that were not
+/// recorded fail.
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    dir: PathBuf,
+}
+
+impl Replayer {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Replayer { dir: dir.into() }
+    }
+
+    fn load(&self, url: &str) -> Result<HttpResponse, Box<dyn Error + Send + Sync>> {
+        let (redacted, meta_path, body_path) = paths(&self.dir, url);
+
+        let meta: Interaction = match fs::read(&meta_path) {
+            Ok(m) => serde_json::from_slice(&m)?,
+            Err(_) => return Err(format!("no recorded response for {}", redacted).into()),
+        };
+        if meta.url != redacted {
+            return Err(format!("no recorded response for {}", redacted).into());
+        }
+
+        Ok(HttpResponse {
+            status: meta.status,
+            body: Bytes::from(fs::read(body_path)?),
+        })
+    }
+}
+
+impl HttpTransport for Replayer {
+    fn get(
+        &self,
+        url: String,
+    ) -> BoxFuture<'_, Result<HttpResponse, Box<dyn Error + Send + Sync>>> {
+        let response = self.load(&url);
+        Box::pin(async move { response })
+    }
+}
+
+#[test]
+fn record_and_replay() {
+    use super::{json, Matcher, MockTransport};
+    use crate::Client;
+    use futures::executor::block_on;
+
+    let dir =
+        std::env::temp_dir().join(format!("wolframalpha_api_cassette_{}", std::process::id()));
+
+    let mock = MockTransport::new().on(
+        Matcher::api("v2/query"),
+        json(super::fixtures::POPULATION_FRANCE),
+    );
+    let recording = Client::with_transport("SECRET-APPID", Recorder::new(&dir, mock).unwrap());
+    let recorded = block_on(recording.query("population france"))
+        .unwrap()
+        .unwrap();
+
+    for entry in fs::read_dir(&dir).unwrap() {
+        let contents = fs::read_to_string(entry.unwrap().path()).unwrap();
+        assert!(!contents.contains("SECRET-APPID"));
+    }
+
+    let replaying = Client::with_transport("OTHER-APPID", Replayer::new(&dir));
+    let replayed = block_on(replaying.query("population france"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(recorded, replayed);
+    assert!(block_on(replaying.query("population sweden")).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
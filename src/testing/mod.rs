@@ -0,0 +1,216 @@
+//! Helpers for testing code built on [`Client`](crate::Client) without
+//! network access
+//!
+//! [`MockTransport`] answers requests with canned responses picked by the
+//! api and parameters of the request:
+//!
+//! ```
+//! use wolframalpha_api::testing::{self, fixtures, Matcher, MockTransport};
+//! use wolframalpha_api::Client;
+//!
+//! let transport = MockTransport::new()
+//!     .on(
+//!         Matcher::api("v2/query").param("input", "population france"),
+//!         testing::json(fixtures::POPULATION_FRANCE),
+//!     )
+//!     .on(Matcher::api("v1/result"), testing::status(501));
+//!
+//! let client = Client::with_transport("APPID", transport.clone());
+//! let result = futures::executor::block_on(client.query("population france"))
+//!     .unwrap()
+//!     .unwrap();
+//!
+//! assert!(result.success);
+//! assert_eq!(transport.requests().len(), 1);
+//! ```
+//!
+//! To test against real responses, record them once with [`Recorder`] and
+//! replay them with [`Replayer`].
+use super::prelude::*;
+use crate::encoding::decode_component;
+use crate::response::{raw_response::RawApiResponse, QueryResult};
+use crate::transport::{HttpResponse, HttpTransport};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+mod cassette;
+pub use crate::encoding::redact_url;
+pub use cassette::{Recorder, Replayer};
+
+/// Recorded full results api responses
+pub mod fixtures {
+    /// A query for `wehter in bostn`, answered with did you mean
+    /// suggestions, the best of which is `weather in`
+    pub const DID_YOU_MEAN: &str = include_str!("fixtures/did_you_mean.json");
+    /// A query made with an invalid AppID
+    pub const INVALID_APPID: &str = include_str!("fixtures/invalid_appid.json");
+    /// A successful query for `population france`
+    pub const POPULATION_FRANCE: &str = include_str!("fixtures/population_france.json");
+}
+
+/// Splits a request url into the api path, such as `v2/query`, and its
+/// decoded query parameters
+pub(crate) fn parse_url(url: &str) -> (String, Vec<(String, String)>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let path = path.split_once("://").map_or(path, |(_, p)| p);
+    let path = path.split_once('/').map_or("", |(_, p)| p);
+
+    let params = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (k, v) = p.split_once('=').unwrap_or((p, ""));
+            (decode_component(k), decode_component(v))
+        })
+        .collect();
+
+    (path.to_string(), params)
+}
+
+/// Selects which requests a canned response is used for
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Matcher {
+    api: Option<String>,
+    params: Vec<(String, String)>,
+}
+
+impl Matcher {
+    /// Matches every request
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// Matches requests to one api, such as `v1/simple` or `v2/query`
+    pub fn api(api: impl Into<String>) -> Self {
+        Matcher {
+            api: Some(api.into()),
+            params: vec![],
+        }
+    }
+
+    /// Additionally requires the request to have the parameter `key` with the
+    /// (unencoded) value `value`
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((key.into(), value.into()));
+        self
+    }
+
+    /// Checks if the matcher accepts `url`
+    pub fn matches(&self, url: &str) -> bool {
+        let (api, params) = parse_url(url);
+
+        self.api.as_ref().map_or(true, |a| *a == api)
+            && self.params.iter().all(|p| params.contains(p))
+    }
+}
+
+/// A 200 response with a json body, such as one of the [`fixtures`]
+pub fn json(body: impl Into<String>) -> HttpResponse {
+    bytes(body.into())
+}
+
+/// A 200 response with an arbitrary body, such as an image
+pub fn bytes(body: impl Into<Bytes>) -> HttpResponse {
+    HttpResponse {
+        status: 200,
+        body: body.into(),
+    }
+}
+
+/// An empty response with the given status, e.g. 501 for a question the
+/// short answers api can't answer
+pub fn status(status: u16) -> HttpResponse {
+    HttpResponse {
+        status,
+        body: Bytes::new(),
+    }
+}
+
+/// A 200 response with the contents of the file at `path` as the body
+pub fn from_file(path: impl AsRef<Path>) -> Result<HttpResponse, Box<dyn Error + Send + Sync>> {
+    Ok(bytes(std::fs::read(path)?))
+}
+
+/// Parses a recorded full results api response, such as one of the
+/// [`fixtures`]
+pub fn parse_query_result(json: &str) -> Result<QueryResult, Box<dyn Error + Send + Sync>> {
+    let raw: RawApiResponse = serde_json::from_str(json)?;
+    Ok(raw.into())
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    routes: Vec<(Matcher, HttpResponse)>,
+    requests: Vec<String>,
+}
+
+/// A transport that answers requests with canned responses instead of
+/// making them. Clones share their routes and recorded requests. With the
+/// `blocking` feature it works with the blocking client too.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers requests accepted by `matcher` with `response`. Routes are
+    /// tried in the order they were added. Requests no route accepts fail.
+    pub fn on(self, matcher: Matcher, response: HttpResponse) -> Self {
+        self.state.lock().unwrap().routes.push((matcher, response));
+        self
+    }
+
+    /// The urls of all requests made so far, in order
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    fn respond(&self, url: String) -> Result<HttpResponse, Box<dyn Error + Send + Sync>> {
+        let mut state = self.state.lock().unwrap();
+
+        let response = state
+            .routes
+            .iter()
+            .find(|(m, _)| m.matches(&url))
+            .map(|(_, r)| r.clone())
+            .ok_or_else(|| format!("no mock response for {}", redact_url(&url)).into());
+
+        state.requests.push(url);
+
+        response
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn get(
+        &self,
+        url: String,
+    ) -> BoxFuture<'_, Result<HttpResponse, Box<dyn Error + Send + Sync>>> {
+        let response = self.respond(url);
+        Box::pin(async move { response })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl crate::blocking::BlockingTransport for MockTransport {
+    fn get(&self, url: &str) -> Result<HttpResponse, Box<dyn Error + Send + Sync>> {
+        self.respond(url.to_string())
+    }
+}
+
+#[test]
+fn matcher_matches() {
+    let url = "http://api.wolframalpha.com/v1/simple?appid=APPID&i=1%2B1+%3D+2&units=metric";
+
+    assert!(Matcher::any().matches(url));
+    assert!(Matcher::api("v1/simple").matches(url));
+    assert!(!Matcher::api("v1/result").matches(url));
+    assert!(Matcher::api("v1/simple").param("i", "1+1 = 2").matches(url));
+    assert!(!Matcher::api("v1/simple").param("i", "1+1").matches(url));
+}
@@ -0,0 +1,113 @@
+use super::prelude::*;
+use bytes::Bytes;
+use futures::channel::oneshot;
+use futures::future::BoxFuture;
+use std::fmt;
+use std::time::Duration;
+
+/// The parts of an http response the client looks at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Bytes,
+}
+
+impl HttpResponse {
+    /// Turns responses with a 4xx or 5xx status into an error
+    pub fn error_for_status(self) -> Result<Self, StatusError> {
+        if self.status >= 400 {
+            Err(StatusError {
+                status: self.status,
+            })
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// An http response with an error status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusError {
+    pub status: u16,
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "http status {}", self.status)
+    }
+}
+
+impl Error for StatusError {}
+
+/// Performs the http requests of a [`Client`](crate::Client). Implement this
+/// to route requests through your own http stack, or to fake responses in
+/// tests.
+pub trait HttpTransport: Send + Sync {
+    /// Performs a GET request to `url`. Responses with error statuses should
+    /// be returned as responses, not as errors.
+    fn get(&self, url: String)
+        -> BoxFuture<'_, Result<HttpResponse, Box<dyn Error + Send + Sync>>>;
+
+    /// Waits for `duration`, for the rate limit and the retry backoff. The
+    /// default waits on a thread of its own, so it works under any executor.
+    /// Override it to use the timer of your runtime instead.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        let (sender, receiver) = oneshot::channel();
+        std::thread::spawn(move || {
+            std::thread::sleep(duration);
+            let _ = sender.send(());
+        });
+        Box::pin(async move {
+            let _ = receiver.await;
+        })
+    }
+}
+
+/// The default transport, built on `reqwest`. Requires the `reqwest`
+/// feature, which is enabled by default.
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses an existing `reqwest` client, for example one configured with a
+    /// proxy or timeouts
+    pub fn from_client(client: reqwest::Client) -> Self {
+        ReqwestTransport { client }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl HttpTransport for ReqwestTransport {
+    fn get(
+        &self,
+        url: String,
+    ) -> BoxFuture<'_, Result<HttpResponse, Box<dyn Error + Send + Sync>>> {
+        Box::pin(async move {
+            // the url contains the AppID, keep it out of error messages
+            let response = self
+                .client
+                .get(url)
+                .send()
+                .await
+                .map_err(|e| e.without_url())?;
+
+            Ok(HttpResponse {
+                status: response.status().as_u16(),
+                body: response.bytes().await.map_err(|e| e.without_url())?,
+            })
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        // reqwest needs a tokio runtime anyway
+        Box::pin(tokio::time::sleep(duration))
+    }
+}